@@ -0,0 +1,318 @@
+//! Rules that adjust grades before GPAs are calculated, for the cases HAC gets wrong.
+//!
+//! [`Adjustments::default`] holds the bundled rules. A TOML file (see [`Adjustments::load`])
+//! replaces them entirely, so copy any bundled rule you want to keep:
+//!
+//! ```toml
+//! [[rules]]
+//! name = "Computer Science A: a 97 is a 100"
+//! name_contains = "Computer Science A"
+//! source = "run"
+//! grade = 97
+//! action = { override = 100 }
+//!
+//! [[rules]]
+//! name = "EA courses don't count"
+//! word = "EA"
+//! source = "transcript"
+//! action = "exclude"
+//!
+//! [[rules]]
+//! name = "Robotics is weighted like an advanced course"
+//! code = "ROB3100"
+//! action = { reweight = 5.5 }
+//! ```
+//!
+//! Every rule that changes an entry is reported as an [`AppliedAdjustment`].
+
+use std::fmt::Display;
+
+#[cfg(feature = "serde")]
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "serde")]
+use anyhow::{Context, Result};
+
+use crate::{
+    catalog::CourseId,
+    transcript::{Grade, Transcript, TranscriptEntry},
+};
+
+/// Where grades come from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum GradeSource {
+    /// The posted transcript.
+    Transcript,
+    /// A report card run's averages, from the assignments page.
+    Run,
+}
+
+/// What a rule does to the entries it matches.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Action {
+    /// Replace the grade.
+    Override(f32),
+    /// Leave the entry out of the transcript, and so the GPA.
+    Exclude,
+    /// Lower the grade to at most this.
+    Cap(f32),
+    /// Replace the weightage (the grade points a 100 earns).
+    Reweight(f32),
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::Override(grade) => write!(f, "grade set to {grade}"),
+            Action::Exclude => write!(f, "excluded"),
+            Action::Cap(grade) => write!(f, "grade capped at {grade}"),
+            Action::Reweight(weightage) => write!(f, "weightage set to {weightage}"),
+        }
+    }
+}
+
+/// A rule. Every condition that is set must hold for an entry to match.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AdjustmentRule {
+    /// Describes the rule in reports.
+    pub name: String,
+    /// The course code (eg. "ENG2200A"), ignoring the section.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub code: Option<String>,
+    /// Text the course name contains.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub name_contains: Option<String>,
+    /// A word the course name contains, on its own.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub word: Option<String>,
+    /// The grade, rounded, must be exactly this. Marks (eg. "P") never match.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub grade: Option<f32>,
+    /// Only adjust grades from this source.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub source: Option<GradeSource>,
+    pub action: Action,
+}
+
+impl AdjustmentRule {
+    pub fn matches(&self, entry: &TranscriptEntry, source: GradeSource) -> bool {
+        let code = || {
+            let expected = CourseId::parse(self.code.as_deref()?)?.code;
+            let actual = CourseId::parse(entry.code.as_deref()?)?.code;
+
+            Some(expected == actual)
+        };
+
+        self.source.is_none_or(|s| s == source)
+            && (self.code.is_none() || code() == Some(true))
+            && self
+                .name_contains
+                .as_ref()
+                .is_none_or(|text| entry.name.contains(text.as_str()))
+            && self
+                .word
+                .as_ref()
+                .is_none_or(|word| entry.name.split_whitespace().any(|w| w == word))
+            && self
+                .grade
+                .is_none_or(|grade| entry.grade.numeric().map(f32::round) == Some(grade))
+    }
+}
+
+/// A rule that changed an entry.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AppliedAdjustment {
+    /// The rule's name.
+    pub rule: String,
+    pub course: String,
+    pub source: GradeSource,
+    pub action: Action,
+    /// The grade before the rule was applied.
+    pub grade: Grade,
+}
+
+/// The rules, applied in order.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Adjustments {
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rules: Vec<AdjustmentRule>,
+}
+
+impl Default for Adjustments {
+    /// The bundled rules, which keep the fixups the grade pages used to make: a 97 in Computer
+    /// Science A counts as a 100, and courses with "EA" in their name are left off the
+    /// transcript.
+    fn default() -> Self {
+        Self {
+            rules: vec![
+                AdjustmentRule {
+                    name: "Computer Science A: a 97 is a 100".to_owned(),
+                    code: None,
+                    name_contains: Some("Computer Science A".to_owned()),
+                    word: None,
+                    grade: Some(97.0),
+                    source: Some(GradeSource::Run),
+                    action: Action::Override(100.0),
+                },
+                AdjustmentRule {
+                    name: "EA courses don't count".to_owned(),
+                    code: None,
+                    name_contains: None,
+                    word: Some("EA".to_owned()),
+                    grade: None,
+                    source: Some(GradeSource::Transcript),
+                    action: Action::Exclude,
+                },
+            ],
+        }
+    }
+}
+
+impl Adjustments {
+    /// No rules at all.
+    pub fn none() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    /// Loads rules from a TOML file, in place of the bundled ones.
+    #[cfg(feature = "serde")]
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("could not read {}", path.display()))?;
+
+        toml::from_str(&contents).with_context(|| format!("invalid {}", path.display()))
+    }
+
+    /// The default config location (eg. `~/.config/hac/adjustments.toml`).
+    #[cfg(feature = "serde")]
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("hac").join("adjustments.toml"))
+    }
+
+    /// Applies every rule to every entry of `transcript`, returning the ones that changed
+    /// something. Rules apply in order, so a later rule sees the grade an earlier one set.
+    pub fn apply(
+        &self,
+        transcript: &mut Transcript,
+        source: GradeSource,
+    ) -> Vec<AppliedAdjustment> {
+        let mut applied = Vec::new();
+
+        transcript.entries.retain_mut(|entry| {
+            for rule in &self.rules {
+                if !rule.matches(entry, source) {
+                    continue;
+                }
+
+                let before = entry.clone();
+
+                match rule.action {
+                    Action::Override(grade) => entry.grade = Grade::Numeric(grade),
+                    Action::Exclude => {}
+                    Action::Cap(cap) => {
+                        if let Some(grade) = entry.grade.numeric() {
+                            entry.grade = Grade::Numeric(grade.min(cap));
+                        }
+                    }
+                    Action::Reweight(weightage) => entry.weightage = weightage,
+                }
+
+                if rule.action == Action::Exclude || *entry != before {
+                    applied.push(AppliedAdjustment {
+                        rule: rule.name.clone(),
+                        course: entry.name.clone(),
+                        source,
+                        action: rule.action,
+                        grade: before.grade,
+                    });
+                }
+
+                if rule.action == Action::Exclude {
+                    return false;
+                }
+            }
+
+            true
+        });
+
+        applied
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_rules() {
+        let adjustments = Adjustments::default();
+
+        let mut run = Transcript {
+            entries: vec![
+                TranscriptEntry::new("AP Computer Science A".to_owned(), 97.0),
+                TranscriptEntry::new("English 2 Adv".to_owned(), 97.0),
+            ],
+        };
+        let applied = adjustments.apply(&mut run, GradeSource::Run);
+
+        assert_eq!(run.entries[0].grade, Grade::Numeric(100.0));
+        assert_eq!(run.entries[1].grade, Grade::Numeric(97.0));
+        assert_eq!(applied.len(), 1);
+        assert_eq!(applied[0].course, "AP Computer Science A");
+        assert_eq!(applied[0].grade, Grade::Numeric(97.0));
+
+        let mut transcript = Transcript {
+            entries: vec![
+                TranscriptEntry::new("AP Computer Science A".to_owned(), 97.0),
+                TranscriptEntry::new("Health EA".to_owned(), 100.0),
+                TranscriptEntry::new("Teamwork".to_owned(), 100.0),
+            ],
+        };
+        let applied = adjustments.apply(&mut transcript, GradeSource::Transcript);
+
+        assert_eq!(transcript.entries.len(), 2);
+        assert_eq!(transcript.entries[0].grade, Grade::Numeric(97.0));
+        assert_eq!(applied.len(), 1);
+        assert_eq!(applied[0].action, Action::Exclude);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn config_rules() {
+        let adjustments: Adjustments = toml::from_str(
+            r#"
+            [[rules]]
+            name = "cap"
+            code = "ENG2200A - 1"
+            action = { cap = 100 }
+
+            [[rules]]
+            name = "reweight"
+            name_contains = "English"
+            action = { reweight = 6.0 }
+            "#,
+        )
+        .unwrap();
+
+        let mut transcript = Transcript {
+            entries: vec![TranscriptEntry::with_id(
+                "ENG2200A - 3",
+                "English 2 Adv".to_owned(),
+                104.0,
+            )],
+        };
+        let applied = adjustments.apply(&mut transcript, GradeSource::Transcript);
+
+        assert_eq!(transcript.entries[0].grade, Grade::Numeric(100.0));
+        assert_eq!(transcript.entries[0].weightage, 6.0);
+        assert_eq!(applied.len(), 2);
+        assert_eq!(applied[1].grade, Grade::Numeric(100.0));
+    }
+}
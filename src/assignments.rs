@@ -0,0 +1,290 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use reqwest::blocking::Client;
+use scraper::{ElementRef, Html};
+
+use crate::{classes, html, selector};
+
+/// The assignments posted for a single class during a report card run.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClassAssignments {
+    /// The name of the class, as shown in the class header.
+    pub course: String,
+    /// The class average for the run, if one has been entered.
+    pub average: Option<f32>,
+    /// Every assignment listed for the class, newest first (as shown by HAC).
+    pub assignments: Vec<Assignment>,
+}
+
+/// A single graded (or not yet graded) assignment.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Assignment {
+    pub name: String,
+    /// The grading category (eg. "Major Grades").
+    pub category: String,
+    pub date_due: Option<NaiveDate>,
+    pub date_assigned: Option<NaiveDate>,
+    /// The points earned, if the assignment has a numeric score.
+    pub score: Option<f32>,
+    /// The points the assignment is out of.
+    pub total_points: Option<f32>,
+    /// Whether the teacher flagged the assignment as missing, late or exempt.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub status: AssignmentStatus,
+}
+
+/// How an assignment was flagged, either by a code in the score column (eg. `M` or `EXC`) or by
+/// the styling of its row.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum AssignmentStatus {
+    /// Nothing flagged: graded normally, or not graded yet.
+    #[default]
+    Normal,
+    Missing,
+    /// Scored a zero without being marked missing.
+    Zero,
+    Late,
+    /// Exempt or excused, so it doesn't count towards the average.
+    Exempt,
+}
+
+impl AssignmentStatus {
+    /// Whether the assignment is hurting (or will hurt) the class average.
+    pub fn needs_attention(self) -> bool {
+        matches!(
+            self,
+            AssignmentStatus::Missing | AssignmentStatus::Zero | AssignmentStatus::Late
+        )
+    }
+}
+
+impl Assignment {
+    /// The score as a percentage of the total points, if both are known.
+    pub fn percentage(&self) -> Option<f32> {
+        match (self.score, self.total_points) {
+            (Some(score), Some(total)) if total > 0.0 => Some(score / total * 100.0),
+            _ => None,
+        }
+    }
+}
+
+/// Gets the assignments of every class for the given report card run (quarter) of this year.
+pub fn get_assignments(client: &Client, quarter: u8) -> Result<Vec<ClassAssignments>> {
+    let document = classes::get_report_card_run(client, quarter)?;
+
+    parse_assignments(&document)
+}
+
+pub(crate) fn parse_assignments(document: &Html) -> Result<Vec<ClassAssignments>> {
+    document
+        .select(selector!(".AssignmentClass"))
+        .map(|class| {
+            let classes::ClassHeader {
+                title: course,
+                average,
+                ..
+            } = classes::parse_class_header(class)?;
+
+            let assignments = class
+                .select(selector!(r#"table[id*="dgCourseAssignments"]"#))
+                .next()
+                .map(parse_assignment_table)
+                .unwrap_or_default();
+
+            Ok(ClassAssignments {
+                course,
+                average,
+                assignments,
+            })
+        })
+        .collect()
+}
+
+fn parse_assignment_table(table: ElementRef) -> Vec<Assignment> {
+    let headers = table
+        .select(selector!("tr.sg-asp-table-header-row"))
+        .next()
+        .map(html::header_names)
+        .unwrap_or_default();
+
+    let column = |name| html::column(&headers, name);
+    let (due, assigned, name, category, score, total) = (
+        column("Date Due"),
+        column("Date Assigned"),
+        column("Assignment"),
+        column("Category"),
+        column("Score"),
+        column("Total Points"),
+    );
+
+    table
+        .select(selector!("tr.sg-asp-table-data-row"))
+        .map(|row| {
+            let elements = row.select(selector!("td")).collect::<Vec<_>>();
+            let cells = elements.iter().map(|e| html::text(*e)).collect::<Vec<_>>();
+            let cell = |index: Option<usize>| {
+                index
+                    .and_then(|i| cells.get(i))
+                    .map(String::as_str)
+                    .unwrap_or("")
+            };
+
+            let score_value = cell(score).parse().ok();
+
+            Assignment {
+                name: cell(name).to_owned(),
+                category: cell(category).to_owned(),
+                date_due: html::parse_date(cell(due)),
+                date_assigned: html::parse_date(cell(assigned)),
+                score: score_value,
+                total_points: cell(total).parse().ok(),
+                status: parse_status(
+                    score.and_then(|i| elements.get(i)).copied(),
+                    cell(score),
+                    score_value,
+                ),
+            }
+        })
+        .collect()
+}
+
+/// Works out an assignment's status from the code in its score column, or failing that from the
+/// classes HAC puts on the score cell to style it. Only whole class names are matched, and never
+/// tooltips, since those hold the assignment's name (eg. "Missing Angles Worksheet").
+fn parse_status(cell: Option<ElementRef>, score: &str, value: Option<f32>) -> AssignmentStatus {
+    let code = match score.to_uppercase().as_str() {
+        "X" | "EX" | "EXC" | "EXMPT" | "EXEMPT" | "EXCUSED" => Some(AssignmentStatus::Exempt),
+        "M" | "MI" | "MSG" | "MISSING" | "I" | "INC" => Some(AssignmentStatus::Missing),
+        "L" | "LATE" => Some(AssignmentStatus::Late),
+        _ => None,
+    };
+
+    if let Some(status) = code {
+        return status;
+    }
+
+    let classes = cell
+        .into_iter()
+        .flat_map(|cell| cell.descendants().filter_map(ElementRef::wrap))
+        .flat_map(|e| e.value().classes())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>();
+    let marked = |name: &str| classes.iter().any(|class| class == name);
+
+    if marked("exempt") || marked("excused") {
+        AssignmentStatus::Exempt
+    } else if marked("missing") {
+        AssignmentStatus::Missing
+    } else if marked("late") {
+        AssignmentStatus::Late
+    } else if value == Some(0.0) {
+        AssignmentStatus::Zero
+    } else {
+        AssignmentStatus::Normal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"
+        <div class="AssignmentClass">
+            <div class="sg-header">
+                <a class="sg-header-heading">ENG2200A - 3    English 2 Adv</a>
+                <span class="sg-header-sub-heading">(Last Updated: 10/01/2024)</span>
+                <span class="sg-header-heading sg-right">Cycle Average 93.50%</span>
+            </div>
+            <div class="sg-content-grid">
+                <table id="plnMain_rptAssigmnetsByCourse_dgCourseAssignments_0" class="sg-asp-table">
+                    <tr class="sg-asp-table-header-row">
+                        <td>Date Due</td><td>Date Assigned</td><td>Assignment</td>
+                        <td>Category</td><td>Score</td><td>Total Points</td>
+                    </tr>
+                    <tr class="sg-asp-table-data-row">
+                        <td>09/27/2024</td><td>09/20/2024</td>
+                        <td><a>  Essay
+                            Draft </a></td>
+                        <td>Major Grades</td><td>87.00</td><td>100.00</td>
+                    </tr>
+                    <tr class="sg-asp-table-data-row">
+                        <td>10/04/2024</td><td>10/01/2024</td><td>Reading Quiz</td>
+                        <td>Minor Grades</td><td></td><td>100.00</td>
+                    </tr>
+                    <tr class="sg-asp-table-data-row">
+                        <td>09/30/2024</td><td>09/23/2024</td><td>Vocab 3</td>
+                        <td>Minor Grades</td><td>M</td><td>100.00</td>
+                    </tr>
+                    <tr class="sg-asp-table-data-row">
+                        <td>09/25/2024</td><td>09/23/2024</td><td>Vocab 2</td>
+                        <td>Minor Grades</td><td class="late">70.00</td><td>100.00</td>
+                    </tr>
+                    <tr class="sg-asp-table-data-row">
+                        <td>09/18/2024</td><td>09/16/2024</td><td>Vocab 1</td>
+                        <td>Minor Grades</td><td>EXC</td><td>100.00</td>
+                    </tr>
+                    <tr class="sg-asp-table-data-row">
+                        <td>09/13/2024</td><td>09/09/2024</td>
+                        <td><a title="Calculate Late Fees">Calculate Late Fees</a></td>
+                        <td>Minor Grades</td><td>95.00</td><td>100.00</td>
+                    </tr>
+                </table>
+            </div>
+        </div>
+        <div class="AssignmentClass">
+            <div class="sg-header">
+                <a class="sg-header-heading">MTH4100 - 1    Calculus BC AP</a>
+                <span class="sg-header-sub-heading">(Last Updated: 10/01/2024)</span>
+                <span class="sg-header-heading sg-right">Cycle Average</span>
+            </div>
+        </div>
+    "#;
+
+    #[test]
+    fn assignments_page() {
+        let classes = parse_assignments(&Html::parse_document(PAGE)).unwrap();
+
+        assert_eq!(classes.len(), 2);
+
+        let english = &classes[0];
+        assert_eq!(english.course, "English 2 Adv");
+        assert_eq!(english.average, Some(93.5));
+        assert_eq!(
+            english.assignments[0],
+            Assignment {
+                name: "Essay Draft".to_owned(),
+                category: "Major Grades".to_owned(),
+                date_due: NaiveDate::from_ymd_opt(2024, 9, 27),
+                date_assigned: NaiveDate::from_ymd_opt(2024, 9, 20),
+                score: Some(87.0),
+                total_points: Some(100.0),
+                status: AssignmentStatus::Normal,
+            }
+        );
+        assert_eq!(english.assignments[1].score, None);
+        assert_eq!(english.assignments[1].percentage(), None);
+        assert_eq!(english.assignments[1].status, AssignmentStatus::Normal);
+
+        let statuses = english.assignments[2..]
+            .iter()
+            .map(|a| a.status)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            [
+                AssignmentStatus::Missing,
+                AssignmentStatus::Late,
+                AssignmentStatus::Exempt,
+                AssignmentStatus::Normal
+            ]
+        );
+
+        assert_eq!(classes[1].course, "Calculus BC AP");
+        assert_eq!(classes[1].average, None);
+        assert!(classes[1].assignments.is_empty());
+    }
+}
@@ -0,0 +1,151 @@
+use anyhow::{anyhow, Result};
+use chrono::{Datelike, NaiveDate};
+use reqwest::blocking::Client;
+use scraper::Html;
+
+use crate::{client, html, selector};
+
+const ATTENDANCE_PAGE_URL: &str =
+    "https://hac.friscoisd.org/HomeAccess/Content/Attendance/MonthlyView.aspx";
+
+/// The attendance events recorded on a single school day.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AttendanceDay {
+    pub date: NaiveDate,
+    pub events: Vec<AttendanceEvent>,
+}
+
+/// A single attendance mark, eg. an absence or a tardy in one period.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AttendanceEvent {
+    /// The period the event applies to, if HAC lists one.
+    pub period: Option<String>,
+    /// The attendance code description (eg. "Tardy", "Excused Absence").
+    pub description: String,
+}
+
+/// Gets every attendance event in the month HAC currently displays (the current month).
+pub fn get_attendance(client: &Client) -> Result<Vec<AttendanceDay>> {
+    let resp = client::fetch_page(client.get(ATTENDANCE_PAGE_URL))?;
+
+    parse_attendance(&Html::parse_document(&resp))
+}
+
+pub(crate) fn parse_attendance(document: &Html) -> Result<Vec<AttendanceDay>> {
+    let calendar = document
+        .select(selector!("#plnMain_cldAttendance"))
+        .next()
+        .ok_or(anyhow!("missing attendance calendar"))?;
+
+    // the calendar's title row holds the displayed month, eg. "October 2024"
+    let month = calendar
+        .select(selector!("td"))
+        .find_map(|cell| {
+            NaiveDate::parse_from_str(&format!("1 {}", html::text(cell)), "%d %B %Y").ok()
+        })
+        .ok_or(anyhow!("missing attendance month"))?;
+
+    let days = calendar
+        .select(selector!("td[title]"))
+        .filter_map(|cell| {
+            let day = html::text(cell).parse::<u32>().ok()?;
+            let date = month.with_day0(day - 1)?;
+
+            let events = parse_events(cell.value().attr("title")?);
+
+            (!events.is_empty()).then_some(AttendanceDay { date, events })
+        })
+        .collect();
+
+    Ok(days)
+}
+
+/// Parses the tooltip of a calendar day, which alternates between period and description lines.
+fn parse_events(title: &str) -> Vec<AttendanceEvent> {
+    let is_period = |line: &str| {
+        line.len() <= 4
+            && line.chars().all(|c| c.is_ascii_alphanumeric())
+            && (line.chars().any(|c| c.is_ascii_digit()) || line.eq_ignore_ascii_case("ADV"))
+    };
+
+    let mut lines = title
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .peekable();
+    let mut events = Vec::new();
+
+    while let Some(line) = lines.next() {
+        if is_period(line) {
+            if let Some(description) = lines.next_if(|next| !is_period(next)) {
+                events.push(AttendanceEvent {
+                    period: Some(line.to_owned()),
+                    description: description.to_owned(),
+                });
+            }
+        } else {
+            events.push(AttendanceEvent {
+                period: None,
+                description: line.to_owned(),
+            });
+        }
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attendance_calendar() {
+        let document = Html::parse_document(
+            r#"
+            <table id="plnMain_cldAttendance">
+                <tr><td><table><tr>
+                    <td><a title="Go to the previous month">&lt;</a></td>
+                    <td>October 2024</td>
+                    <td><a title="Go to the next month">&gt;</a></td>
+                </tr></table></td></tr>
+                <tr><th>Sun</th><th>Mon</th></tr>
+                <tr>
+                    <td>6</td>
+                    <td style="background-color:#FFFF00;" title="1&#10;Tardy&#10;3&#10;Excused Absence">7</td>
+                    <td title="School Holiday">14</td>
+                </tr>
+            </table>
+            "#,
+        );
+
+        let days = parse_attendance(&document).unwrap();
+
+        assert_eq!(
+            days,
+            vec![
+                AttendanceDay {
+                    date: NaiveDate::from_ymd_opt(2024, 10, 7).unwrap(),
+                    events: vec![
+                        AttendanceEvent {
+                            period: Some("1".to_owned()),
+                            description: "Tardy".to_owned(),
+                        },
+                        AttendanceEvent {
+                            period: Some("3".to_owned()),
+                            description: "Excused Absence".to_owned(),
+                        },
+                    ],
+                },
+                AttendanceDay {
+                    date: NaiveDate::from_ymd_opt(2024, 10, 14).unwrap(),
+                    events: vec![AttendanceEvent {
+                        period: None,
+                        description: "School Holiday".to_owned(),
+                    }],
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use std::{
+    fs,
+    path::Path,
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Context, Result};
+use hac::{
+    client::Client, Adjustments, AppliedAdjustment, Catalog, Course, MergeStrategy, Transcript,
+};
+use serde::Serialize;
+
+use crate::{
+    collect_transcripts,
+    output::{self, Output, Schedule},
+};
+
+/// A single student account listed in a batch credentials file.
+struct Account {
+    username: String,
+    password: String,
+}
+
+/// The combined results of a batch run, one entry per account in file order.
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct BatchReport(Vec<AccountReport>);
+
+/// The outcome for a single account. Either `error` is set, or all of the other fields are.
+#[derive(Serialize)]
+pub struct AccountReport {
+    username: String,
+    cumulative_gpa: Option<f32>,
+    cumulative_entries: Option<usize>,
+    /// The adjustment rules that changed or excluded entries in the cumulative GPA.
+    adjustments: Option<Vec<AppliedAdjustment>>,
+    schedule: Option<Vec<Course>>,
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct AccountRow {
+    username: String,
+    cumulative_gpa: Option<f32>,
+    courses: Option<usize>,
+    error: Option<String>,
+}
+
+impl Output for BatchReport {
+    type Row = AccountRow;
+
+    fn kind(&self) -> &'static str {
+        "batch"
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        self.0
+            .iter()
+            .map(|account| AccountRow {
+                username: account.username.clone(),
+                cumulative_gpa: account.cumulative_gpa,
+                courses: account.schedule.as_ref().map(Vec::len),
+                error: account.error.clone(),
+            })
+            .collect()
+    }
+
+    fn print_table(&self) {
+        let failed = self.0.iter().filter(|a| a.error.is_some()).count();
+
+        println!(
+            "Batch report for {} accounts ({} succeeded, {failed} failed)",
+            self.0.len(),
+            self.0.len() - failed,
+        );
+
+        for account in &self.0 {
+            println!();
+            println!("{}", account.username);
+
+            if let Some(error) = &account.error {
+                println!("\tfailed: {error}");
+                continue;
+            }
+
+            if let (Some(gpa), Some(entries)) = (account.cumulative_gpa, account.cumulative_entries)
+            {
+                println!("Cumulative GPA with {entries} total entries: {gpa}");
+            }
+
+            if let Some(adjustments) = &account.adjustments {
+                output::print_adjustments(adjustments);
+            }
+
+            if let Some(schedule) = &account.schedule {
+                Schedule(schedule.clone()).print_table();
+            }
+        }
+    }
+}
+
+/// Everything collected for one account during a batch run.
+struct StudentSummary {
+    schedule: Vec<Course>,
+    cumulative: Transcript,
+    adjustments: Vec<AppliedAdjustment>,
+}
+
+/// Enforces a minimum delay between consecutive HAC sessions.
+struct RateLimiter {
+    interval: Duration,
+    last: Option<Instant>,
+}
+
+impl RateLimiter {
+    fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last: None,
+        }
+    }
+
+    /// Blocks until at least `interval` has passed since the previous call.
+    fn wait(&mut self) {
+        if let Some(last) = self.last {
+            if let Some(remaining) = self.interval.checked_sub(last.elapsed()) {
+                thread::sleep(remaining);
+            }
+        }
+
+        self.last = Some(Instant::now());
+    }
+}
+
+/// Reads a credentials file containing one `username:password` pair per line. Blank lines and
+/// lines starting with `#` are ignored.
+fn read_accounts(path: &Path) -> Result<Vec<Account>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("could not read credentials file {}", path.display()))?;
+
+    contents
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line_number, line)| {
+            let (username, password) = line
+                .split_once(':')
+                .ok_or(anyhow!("line {line_number}: expected `username:password`"))?;
+
+            Ok(Account {
+                username: username.trim().to_owned(),
+                password: password.to_owned(),
+            })
+        })
+        .collect()
+}
+
+fn summarize_account(
+    account: &Account,
+    catalog: &Catalog,
+    adjustments: &Adjustments,
+) -> Result<StudentSummary> {
+    let client = Client::new(&account.username, &account.password)?;
+    let (transcripts, adjustments) = collect_transcripts(&client, catalog, adjustments, false)?;
+
+    Ok(StudentSummary {
+        schedule: client.get_schedule()?,
+        cumulative: Transcript::combine(&transcripts, MergeStrategy::Average),
+        adjustments,
+    })
+}
+
+/// Logs in as every account in `accounts_path` (one at a time, at most once every `delay`) and
+/// collects a combined report. A failure for one account is recorded and does not stop the batch.
+pub fn run(
+    accounts_path: &Path,
+    delay: Duration,
+    catalog: &Catalog,
+    adjustments: &Adjustments,
+) -> Result<BatchReport> {
+    let accounts = read_accounts(accounts_path)?;
+    let mut limiter = RateLimiter::new(delay);
+
+    let mut reports = Vec::with_capacity(accounts.len());
+
+    for (i, account) in accounts.iter().enumerate() {
+        limiter.wait();
+
+        eprintln!(
+            "[{}/{}] fetching data for {}",
+            i + 1,
+            accounts.len(),
+            account.username
+        );
+
+        let report = match summarize_account(account, catalog, adjustments) {
+            Ok(summary) => AccountReport {
+                username: account.username.clone(),
+                cumulative_gpa: Some(summary.cumulative.gpa()),
+                cumulative_entries: Some(summary.cumulative.entries.len()),
+                adjustments: Some(summary.adjustments),
+                schedule: Some(summary.schedule),
+                error: None,
+            },
+            Err(e) => AccountReport {
+                username: account.username.clone(),
+                cumulative_gpa: None,
+                cumulative_entries: None,
+                adjustments: None,
+                schedule: None,
+                error: Some(format!("{e:#}")),
+            },
+        };
+
+        reports.push(report);
+    }
+
+    Ok(BatchReport(reports))
+}
@@ -0,0 +1,303 @@
+use std::path::PathBuf;
+
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
+use hac::{credentials, GpaScale};
+
+/// Query Frisco ISD's Home Access Center (HAC) from the command line.
+///
+/// Credentials are read, in order, from the HAC_USERNAME/HAC_PASSWORD environment variables, the
+/// OS keyring (if built with the `keyring` feature), the credentials file in your config
+/// directory, and finally an interactive prompt. Use `login` to store them.
+#[derive(Debug, Parser)]
+#[command(version)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+
+    /// How results are printed.
+    #[arg(long, short, global = true, value_enum, default_value_t)]
+    pub format: Format,
+
+    /// The stored credentials profile to log in with.
+    #[arg(
+        long,
+        short,
+        global = true,
+        env = "HAC_PROFILE",
+        default_value = credentials::DEFAULT_PROFILE
+    )]
+    pub profile: String,
+
+    /// The snapshot database to use. Defaults to `history.sqlite3` in your data directory.
+    #[cfg(feature = "sqlite")]
+    #[arg(long, global = true, env = "HAC_DB")]
+    pub db: Option<PathBuf>,
+
+    /// Course catalog overrides, used to classify and weight courses. Defaults to `catalog.toml`
+    /// in your config directory, if present.
+    #[arg(long, global = true)]
+    pub catalog: Option<PathBuf>,
+
+    /// Grade adjustment rules, applied before GPAs are calculated. Defaults to
+    /// `adjustments.toml` in your config directory if present, and otherwise the bundled rules.
+    #[arg(long, global = true)]
+    pub adjustments: Option<PathBuf>,
+
+    /// Print progress while talking to HAC.
+    #[arg(long, short, global = true, action = ArgAction::Count)]
+    pub verbose: u8,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Check a username and password against HAC and store them for the profile.
+    Login,
+    /// Show the classes you are currently enrolled in.
+    Schedule,
+    /// Show each course's code, section, subject area and level, from the course catalog.
+    Courses,
+    /// List your teachers, their email addresses and the courses they teach.
+    Teachers,
+    /// Show the most recently posted transcript.
+    Transcript,
+    /// Show class averages for a report card run.
+    Grades {
+        /// The report card run (quarter), from 1 to 4.
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=4))]
+        run: u8,
+    },
+    /// Show every class's assignments for a report card run.
+    Assignments {
+        /// The report card run (quarter), from 1 to 4.
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=4))]
+        run: u8,
+    },
+    /// Show missing, late and zero assignments in the current run, sorted by how much they are
+    /// costing each class average.
+    Missing,
+    /// Show the cumulative GPA, combining the transcript with this year's grades.
+    Gpa {
+        /// The grade point scale to use.
+        #[arg(long, value_enum, default_value_t)]
+        scale: Scale,
+    },
+    /// Show credits earned and in progress towards each graduation requirement.
+    Graduation {
+        /// The graduation plan file. Defaults to `graduation.toml` in your config directory if
+        /// present, and otherwise the Texas Foundation plan with an endorsement.
+        #[arg(long)]
+        plan: Option<PathBuf>,
+
+        /// Show HAC's own view from the Graduation Progress page instead of working it out from
+        /// the transcript and schedule.
+        #[arg(long, conflicts_with = "plan")]
+        official: bool,
+    },
+    /// Estimate where class rank will move with this year's grades, and the GPA the top 10% and
+    /// 25% will take at graduation.
+    Rank {
+        /// The assumed standard deviation of the class's weighted GPAs, in grade points.
+        #[arg(long, default_value_t = hac::DEFAULT_GPA_SPREAD)]
+        spread: f32,
+
+        /// The graduation plan file, used to count the courses left. Defaults to
+        /// `graduation.toml` in your config directory if present, and otherwise the Texas
+        /// Foundation plan with an endorsement.
+        #[arg(long)]
+        plan: Option<PathBuf>,
+    },
+    /// Show this month's attendance.
+    Attendance,
+    /// Show the class meeting right now and the next one, using the bell schedule and A/B day
+    /// calendar.
+    ///
+    /// The schedule comes from the latest stored snapshot or the schedule cached by the last
+    /// fetch, so HAC is only contacted when neither exists.
+    Now {
+        /// The calendar file. Defaults to `calendar.toml` in your config directory.
+        #[arg(long)]
+        calendar: Option<PathBuf>,
+
+        /// Fetch the schedule from HAC even if a stored one is available.
+        #[arg(long)]
+        refresh: bool,
+    },
+    /// Export to iCalendar (.ics) for importing into calendar apps.
+    #[command(subcommand)]
+    Ics(IcsCommand),
+    /// Save a snapshot of the schedule, grades, assignments and transcript to the database.
+    ///
+    /// Meant to be run periodically (eg. from cron); exits with a non-zero status on failure.
+    #[cfg(feature = "sqlite")]
+    Snapshot,
+    /// Show what changed between two snapshots: new assignments, changed scores and averages,
+    /// and courses added to or dropped from the schedule.
+    #[cfg(feature = "sqlite")]
+    Changes {
+        /// The snapshot to compare against: a snapshot id, a date (YYYY-MM-DD) or an RFC 3339
+        /// time. Defaults to the snapshot before the latest one.
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Take (and save) a new snapshot from HAC instead of using the latest stored one.
+        #[arg(long)]
+        fetch: bool,
+    },
+    /// Show how each class's average has moved over the stored snapshots of the current run,
+    /// with the assignment behind the biggest change and a projected end-of-run average.
+    #[cfg(feature = "sqlite")]
+    Trends {
+        /// The last day of the report card run (YYYY-MM-DD), used for projections. Defaults to
+        /// nine weeks after the first assignment of the run.
+        #[arg(long)]
+        run_end: Option<chrono::NaiveDate>,
+    },
+    /// Poll HAC periodically and send a notification whenever grades change.
+    #[cfg(feature = "sqlite")]
+    Watch(WatchArgs),
+    /// Open an interactive dashboard in the terminal.
+    #[cfg(feature = "tui")]
+    Dashboard,
+    /// Fetch the schedule and cumulative GPA of every account in a credentials file.
+    Batch {
+        /// A file with one `username:password` pair per line.
+        accounts: PathBuf,
+
+        /// The minimum number of seconds between logins.
+        #[arg(long, default_value_t = 5)]
+        delay: u64,
+    },
+}
+
+#[cfg(feature = "sqlite")]
+#[derive(Debug, clap::Args)]
+pub struct WatchArgs {
+    /// Minutes between polls.
+    #[arg(long, default_value_t = 30, value_parser = clap::value_parser!(u64).range(1..))]
+    pub interval: u64,
+
+    /// The fraction of each delay that is randomly added or removed, from 0 to 1.
+    #[arg(long, default_value_t = 0.1, value_parser = parse_fraction)]
+    pub jitter: f64,
+
+    /// The longest wait, in minutes, between retries while HAC is failing.
+    #[arg(long, default_value_t = 120, value_parser = clap::value_parser!(u64).range(1..))]
+    pub max_backoff: u64,
+
+    /// Don't poll during this local time window (eg. `22:00-06:00`).
+    #[arg(long)]
+    pub quiet_hours: Option<hac::poll::QuietHours>,
+
+    /// Where to remember the last poll between restarts. Defaults to `watch-<profile>.json` in
+    /// your data directory.
+    #[arg(long)]
+    pub state: Option<PathBuf>,
+
+    /// The notifier config file. Defaults to `notify.toml` in your config directory, if present.
+    #[arg(long)]
+    pub notify_config: Option<PathBuf>,
+
+    /// Also POST changes as JSON to this URL (Discord and Slack webhooks work).
+    #[arg(long)]
+    pub webhook: Vec<String>,
+
+    /// Also push changes to this ntfy topic URL.
+    #[arg(long)]
+    pub ntfy: Vec<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum IcsCommand {
+    /// Export the schedule for the school year as recurring events, using the bell schedule and
+    /// A/B day calendar.
+    Schedule {
+        /// The calendar file. Defaults to `calendar.toml` in your config directory.
+        #[arg(long)]
+        calendar: Option<PathBuf>,
+
+        /// Where to write the .ics file. Defaults to stdout.
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Export upcoming and missing assignments as events on their due dates.
+    Assignments {
+        /// Where to write the .ics file. Defaults to stdout.
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+
+        /// Add a reminder this many hours before the start of the due date (eg. 15 for 9 AM the
+        /// day before). May be repeated.
+        #[arg(long = "remind", default_value = "15")]
+        remind_hours: Vec<i64>,
+    },
+    /// Serve the assignments feed over HTTP, so calendar apps can subscribe to it.
+    Serve {
+        /// The address to listen on.
+        #[arg(long, default_value = "127.0.0.1:8077")]
+        bind: String,
+
+        /// Minutes to reuse the feed before fetching assignments from HAC again.
+        #[arg(long, default_value_t = 30)]
+        refresh: u64,
+
+        /// Add a reminder this many hours before the start of the due date. May be repeated.
+        #[arg(long = "remind", default_value = "15")]
+        remind_hours: Vec<i64>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+pub enum Format {
+    /// Human readable text.
+    #[default]
+    Table,
+    /// JSON wrapped in a versioned envelope.
+    Json,
+    /// One comma separated row per item.
+    Csv,
+    /// TOML wrapped in a versioned envelope.
+    Toml,
+}
+
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+pub enum Scale {
+    /// The district's weighted scale (regular 5.0, Adv 5.5, AP 6.0).
+    #[default]
+    Weighted,
+    /// The unweighted 4.0 scale.
+    Unweighted,
+}
+
+impl From<Scale> for GpaScale {
+    fn from(scale: Scale) -> Self {
+        match scale {
+            Scale::Weighted => GpaScale::Weighted,
+            Scale::Unweighted => GpaScale::Unweighted,
+        }
+    }
+}
+
+/// Parses a number from 0 to 1.
+#[cfg(feature = "sqlite")]
+fn parse_fraction(text: &str) -> Result<f64, String> {
+    match text.parse::<f64>() {
+        Ok(fraction) if (0.0..=1.0).contains(&fraction) => Ok(fraction),
+        Ok(_) => Err("must be from 0 to 1".to_owned()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn watch_timing() {
+        let watch = |args: &[&str]| Cli::try_parse_from(["hac", "watch"].iter().chain(args));
+
+        assert!(watch(&[]).is_ok());
+        assert!(watch(&["--interval", "0"]).is_err());
+        assert!(watch(&["--max-backoff", "0"]).is_err());
+        assert!(watch(&["--jitter", "1.5"]).is_err());
+    }
+}
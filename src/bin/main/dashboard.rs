@@ -0,0 +1,526 @@
+//! An interactive terminal dashboard, enabled with the `tui` feature.
+//!
+//! Keys: `Tab`/`←`/`→` (or `1`-`5`) switch tabs, `↑`/`↓` move the selection, `r` re-fetches
+//! everything from HAC and `q` quits. On the GPA tab, `e` edits the selected class's grade for a
+//! what-if projection, `x` clears that edit and `c` clears all of them.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use hac::{
+    client::Client, Adjustments, AppliedAdjustment, Catalog, ClassAssignments, Course, Grade,
+    MergeStrategy, Transcript,
+};
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::Line,
+    widgets::{Block, Cell, List, ListState, Paragraph, Row, Table, TableState, Tabs},
+    DefaultTerminal, Frame,
+};
+
+use crate::collect_transcripts;
+
+const TABS: [&str; 5] = ["Schedule", "Grades", "Assignments", "Transcript", "GPA"];
+
+const SCHEDULE: usize = 0;
+const GRADES: usize = 1;
+const ASSIGNMENTS: usize = 2;
+const TRANSCRIPT: usize = 3;
+const GPA: usize = 4;
+
+/// Everything shown by the dashboard, fetched in one go.
+struct Data {
+    schedule: Vec<Course>,
+    /// The posted transcript, followed by this year's grades for each run that has any.
+    transcripts: Vec<Transcript>,
+    /// The adjustment rules that changed or excluded entries in `transcripts`.
+    adjustments: Vec<AppliedAdjustment>,
+    /// The assignments for the most recent run with grades.
+    assignments: Vec<ClassAssignments>,
+}
+
+impl Data {
+    fn fetch(client: &Client, catalog: &Catalog, adjustments: &Adjustments) -> Result<Self> {
+        let mut schedule = client.get_schedule()?;
+        schedule.sort_by_key(|c| c.period().map(ToString::to_string));
+
+        let (transcripts, adjustments) = collect_transcripts(client, catalog, adjustments, false)?;
+        let run = transcripts.len().saturating_sub(1).max(1) as u8;
+
+        Ok(Self {
+            schedule,
+            assignments: client.get_assignments(run)?,
+            transcripts,
+            adjustments,
+        })
+    }
+
+    /// The grades for the most recent run, if any have been entered this year.
+    fn current_grades(&self) -> Option<&Transcript> {
+        self.transcripts.get(1..).and_then(<[_]>::last)
+    }
+}
+
+enum Mode {
+    Browse,
+    /// Typing a what-if grade for the selected class.
+    Editing(String),
+}
+
+struct App<'a> {
+    client: &'a Client,
+    catalog: &'a Catalog,
+    adjustments: &'a Adjustments,
+    data: Data,
+    tab: usize,
+    selections: [usize; TABS.len()],
+    /// What-if grades for the current run, by class name.
+    overrides: HashMap<String, f32>,
+    mode: Mode,
+    status: String,
+    quit: bool,
+}
+
+/// Runs the dashboard until the user quits.
+pub fn run(client: &Client, catalog: &Catalog, adjustments: &Adjustments) -> Result<()> {
+    eprintln!("Fetching data from HAC...");
+
+    let mut app = App {
+        client,
+        catalog,
+        adjustments,
+        data: Data::fetch(client, catalog, adjustments)?,
+        tab: SCHEDULE,
+        selections: [0; TABS.len()],
+        overrides: HashMap::new(),
+        mode: Mode::Browse,
+        status: String::new(),
+        quit: false,
+    };
+
+    let mut terminal = ratatui::init();
+    let result = app.event_loop(&mut terminal);
+    ratatui::restore();
+
+    result
+}
+
+impl App<'_> {
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        while !self.quit {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            match &mut self.mode {
+                Mode::Browse => self.browse_key(key.code, terminal)?,
+                Mode::Editing(input) => match key.code {
+                    KeyCode::Char(c) if c.is_ascii_digit() || c == '.' => input.push(c),
+                    KeyCode::Backspace => {
+                        input.pop();
+                    }
+                    KeyCode::Enter => {
+                        match input.parse::<f32>() {
+                            Ok(grade) if (0.0..=110.0).contains(&grade) => {
+                                if let Some(name) = self.selected_grade_name() {
+                                    self.overrides.insert(name, grade);
+                                }
+                                self.status.clear();
+                            }
+                            _ => self.status = format!("invalid grade `{input}`"),
+                        }
+                        self.mode = Mode::Browse;
+                    }
+                    KeyCode::Esc => self.mode = Mode::Browse,
+                    _ => {}
+                },
+            }
+        }
+
+        Ok(())
+    }
+
+    fn browse_key(&mut self, code: KeyCode, terminal: &mut DefaultTerminal) -> Result<()> {
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Tab | KeyCode::Right | KeyCode::Char('l') => {
+                self.tab = (self.tab + 1) % TABS.len()
+            }
+            KeyCode::BackTab | KeyCode::Left | KeyCode::Char('h') => {
+                self.tab = (self.tab + TABS.len() - 1) % TABS.len()
+            }
+            KeyCode::Char(c @ '1'..='5') => self.tab = c as usize - '1' as usize,
+            KeyCode::Down | KeyCode::Char('j') => {
+                let len = self.row_count();
+                let selected = &mut self.selections[self.tab];
+                *selected = (*selected + 1).min(len.saturating_sub(1));
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                let selected = &mut self.selections[self.tab];
+                *selected = selected.saturating_sub(1);
+            }
+            KeyCode::Char('r') => {
+                self.status = "Refreshing from HAC...".to_owned();
+                terminal.draw(|frame| self.draw(frame))?;
+
+                self.status = match Data::fetch(self.client, self.catalog, self.adjustments) {
+                    Ok(data) => {
+                        self.data = data;
+                        self.selections = [0; TABS.len()];
+                        "Refreshed".to_owned()
+                    }
+                    Err(e) => format!("refresh failed: {e:#}"),
+                };
+            }
+            KeyCode::Char('e') if self.tab == GPA && self.selected_grade_name().is_some() => {
+                self.mode = Mode::Editing(String::new())
+            }
+            KeyCode::Char('x') if self.tab == GPA => {
+                if let Some(name) = self.selected_grade_name() {
+                    self.overrides.remove(&name);
+                }
+            }
+            KeyCode::Char('c') if self.tab == GPA => self.overrides.clear(),
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn row_count(&self) -> usize {
+        match self.tab {
+            SCHEDULE => self.data.schedule.len(),
+            GRADES | GPA => self.data.current_grades().map_or(0, |t| t.entries.len()),
+            ASSIGNMENTS => self.data.assignments.len(),
+            TRANSCRIPT => self.data.transcripts.first().map_or(0, |t| t.entries.len()),
+            _ => 0,
+        }
+    }
+
+    fn selected_grade_name(&self) -> Option<String> {
+        self.data
+            .current_grades()?
+            .entries
+            .get(self.selections[GPA])
+            .map(|e| e.name.clone())
+    }
+
+    /// The cumulative GPA with every what-if grade applied to the current run.
+    fn what_if_gpa(&self) -> Option<f32> {
+        let mut transcripts = self.data.transcripts.clone();
+
+        if transcripts.len() < 2 {
+            return None;
+        }
+
+        for entry in &mut transcripts.last_mut()?.entries {
+            if let Some(grade) = self.overrides.get(&entry.name) {
+                entry.grade = Grade::Numeric(*grade);
+            }
+        }
+
+        Some(Transcript::combine(&transcripts, MergeStrategy::Average).gpa())
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [tabs, body, footer] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        frame.render_widget(
+            Tabs::new(TABS)
+                .select(self.tab)
+                .block(Block::bordered().title(" HAC "))
+                .highlight_style(Style::new().bold().reversed()),
+            tabs,
+        );
+
+        match self.tab {
+            SCHEDULE => self.draw_schedule(frame, body),
+            GRADES => self.draw_grades(frame, body),
+            ASSIGNMENTS => self.draw_assignments(frame, body),
+            TRANSCRIPT => self.draw_transcript(frame, body),
+            _ => self.draw_gpa(frame, body),
+        }
+
+        let help = match &self.mode {
+            Mode::Editing(input) => {
+                format!("what-if grade: {input}█  (enter to apply, esc to cancel)")
+            }
+            Mode::Browse if !self.status.is_empty() => self.status.clone(),
+            Mode::Browse if self.tab == GPA => {
+                "↑↓ select  e edit grade  x clear  c clear all  r refresh  q quit".to_owned()
+            }
+            Mode::Browse => "←→ tabs  ↑↓ select  r refresh  q quit".to_owned(),
+        };
+
+        frame.render_widget(Paragraph::new(help).dim(), footer);
+    }
+
+    fn draw_table<'a>(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        title: &'a str,
+        header: impl IntoIterator<Item = &'a str>,
+        rows: Vec<Row<'a>>,
+        widths: impl IntoIterator<Item = Constraint>,
+    ) {
+        let table = Table::new(rows, widths)
+            .header(Row::new(header).bold().underlined())
+            .block(Block::bordered().title(title))
+            .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+
+        let mut state = TableState::new().with_selected(self.selections[self.tab]);
+        frame.render_stateful_widget(table, area, &mut state);
+    }
+
+    fn draw_schedule(&self, frame: &mut Frame, area: Rect) {
+        let rows = self
+            .data
+            .schedule
+            .iter()
+            .map(|c| {
+                Row::new([
+                    c.periods()
+                        .iter()
+                        .map(|p| p.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                    c.name().to_owned(),
+                    c.teacher().name.clone(),
+                    c.classroom().to_owned(),
+                ])
+            })
+            .collect();
+
+        self.draw_table(
+            frame,
+            area,
+            " Schedule ",
+            ["Period", "Course", "Teacher", "Room"],
+            rows,
+            [
+                Constraint::Length(8),
+                Constraint::Fill(2),
+                Constraint::Fill(1),
+                Constraint::Length(10),
+            ],
+        );
+    }
+
+    fn draw_grades(&self, frame: &mut Frame, area: Rect) {
+        let rows = self
+            .data
+            .current_grades()
+            .iter()
+            .flat_map(|t| &t.entries)
+            .map(|e| {
+                Row::new([
+                    Cell::new(e.name.clone()),
+                    Cell::new(e.grade.to_string()).fg(mark_color(&e.grade)),
+                ])
+            })
+            .collect();
+
+        self.draw_table(
+            frame,
+            area,
+            " Current grades ",
+            ["Course", "Average"],
+            rows,
+            [Constraint::Fill(1), Constraint::Length(8)],
+        );
+    }
+
+    fn draw_assignments(&self, frame: &mut Frame, area: Rect) {
+        let [classes, assignments] =
+            Layout::horizontal([Constraint::Percentage(30), Constraint::Fill(1)]).areas(area);
+
+        let list = List::new(self.data.assignments.iter().map(|c| c.course.clone()))
+            .block(Block::bordered().title(" Classes "))
+            .highlight_style(Style::new().reversed());
+
+        let mut state = ListState::default().with_selected(Some(self.selections[ASSIGNMENTS]));
+        frame.render_stateful_widget(list, classes, &mut state);
+
+        let rows = self
+            .data
+            .assignments
+            .get(self.selections[ASSIGNMENTS])
+            .iter()
+            .flat_map(|c| &c.assignments)
+            .map(|a| {
+                let score = match a.percentage() {
+                    Some(percentage) => {
+                        Cell::new(format!("{percentage:.0}%")).fg(grade_color(percentage))
+                    }
+                    None => Cell::new("-"),
+                };
+
+                Row::new([
+                    Cell::new(a.date_due.map(|d| d.to_string()).unwrap_or_default()),
+                    Cell::new(a.name.clone()),
+                    Cell::new(a.category.clone()),
+                    score,
+                ])
+            })
+            .collect::<Vec<_>>();
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(10),
+                Constraint::Fill(2),
+                Constraint::Fill(1),
+                Constraint::Length(6),
+            ],
+        )
+        .header(
+            Row::new(["Due", "Assignment", "Category", "Score"])
+                .bold()
+                .underlined(),
+        )
+        .block(Block::bordered().title(" Assignments "));
+
+        frame.render_widget(table, assignments);
+    }
+
+    fn draw_transcript(&self, frame: &mut Frame, area: Rect) {
+        let rows = self
+            .data
+            .transcripts
+            .first()
+            .iter()
+            .flat_map(|t| &t.entries)
+            .map(|e| {
+                Row::new([
+                    Cell::new(e.year.clone().unwrap_or_default()),
+                    Cell::new(e.name.clone()),
+                    Cell::new(e.grade.to_string()).fg(mark_color(&e.grade)),
+                    Cell::new(e.gpa().map(|gpa| format!("{gpa:.1}")).unwrap_or_default()),
+                ])
+            })
+            .collect();
+
+        self.draw_table(
+            frame,
+            area,
+            " Transcript ",
+            ["Year", "Course", "Grade", "Points"],
+            rows,
+            [
+                Constraint::Length(10),
+                Constraint::Fill(1),
+                Constraint::Length(6),
+                Constraint::Length(6),
+            ],
+        );
+    }
+
+    fn draw_gpa(&self, frame: &mut Frame, area: Rect) {
+        let cumulative = Transcript::combine(&self.data.transcripts, MergeStrategy::Average);
+
+        let mut lines = self
+            .data
+            .transcripts
+            .iter()
+            .enumerate()
+            .map(|(i, t)| {
+                let source = match i {
+                    0 => "Posted transcript".to_owned(),
+                    quarter => format!("Quarter {quarter}"),
+                };
+
+                Line::from(format!("{source}: {:.4}", t.gpa()))
+            })
+            .collect::<Vec<_>>();
+
+        lines.push(Line::from(format!("Cumulative: {:.4}", cumulative.gpa())).bold());
+
+        if let Some(gpa) = self.what_if_gpa().filter(|_| !self.overrides.is_empty()) {
+            lines.push(
+                Line::from(format!("What-if cumulative: {gpa:.4}"))
+                    .bold()
+                    .yellow(),
+            );
+        }
+
+        // name the rules behind the GPAs, like the `gpa` command does
+        lines.extend(self.data.adjustments.iter().map(|applied| {
+            Line::from(format!(
+                "Adjusted {} ({}): {}, by rule \"{}\"",
+                applied.course, applied.grade, applied.action, applied.rule
+            ))
+            .dark_gray()
+        }));
+
+        let [summary, what_if] = Layout::vertical([
+            Constraint::Length(lines.len() as u16 + 2),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title(" GPA ")),
+            summary,
+        );
+
+        let rows = self
+            .data
+            .current_grades()
+            .iter()
+            .flat_map(|t| &t.entries)
+            .map(|e| {
+                let what_if = match self.overrides.get(&e.name) {
+                    Some(grade) => Cell::new(grade.to_string()).fg(grade_color(*grade)).bold(),
+                    None => Cell::new(""),
+                };
+
+                Row::new([
+                    Cell::new(e.name.clone()),
+                    Cell::new(e.grade.to_string()).fg(mark_color(&e.grade)),
+                    what_if,
+                ])
+            })
+            .collect();
+
+        self.draw_table(
+            frame,
+            what_if,
+            " What if ",
+            ["Course", "Grade", "What-if"],
+            rows,
+            [
+                Constraint::Fill(1),
+                Constraint::Length(8),
+                Constraint::Length(8),
+            ],
+        );
+    }
+}
+
+/// Colors numeric grades with [`grade_color`], and marks (eg. "P") gray.
+fn mark_color(grade: &Grade) -> Color {
+    grade.numeric().map_or(Color::DarkGray, grade_color)
+}
+
+fn grade_color(grade: f32) -> Color {
+    match grade.round() {
+        90.0.. => Color::Green,
+        80.0.. => Color::Blue,
+        70.0.. => Color::Yellow,
+        _ => Color::Red,
+    }
+}
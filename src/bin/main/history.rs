@@ -0,0 +1,397 @@
+//! Commands backed by the local snapshot database, enabled with the `sqlite` feature.
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Local, NaiveDate};
+use hac::{
+    client::Client,
+    credentials,
+    store::{SnapshotStore, StoredSnapshot},
+    ClassTrend, Course, GradeChanges,
+};
+use serde::Serialize;
+
+use crate::{
+    cli::Cli,
+    connect,
+    output::Output,
+    table::{sparkline, Cell, Color, Table},
+};
+
+pub fn open_store(cli: &Cli) -> Result<SnapshotStore> {
+    let path = match &cli.db {
+        Some(path) => path.clone(),
+        None => SnapshotStore::default_path().context("could not find a data directory")?,
+    };
+
+    SnapshotStore::open(&path)
+}
+
+/// The schedule in the profile's latest stored snapshot, if there is one. Credentials are only
+/// looked up (to find the student), never prompted for.
+pub fn stored_schedule(cli: &Cli) -> Option<Vec<Course>> {
+    let providers = credentials::default_providers()
+        .into_iter()
+        .filter(|p| p.name() != "prompt")
+        .collect::<Vec<_>>();
+    let student = credentials::resolve(&providers, &cli.profile)
+        .ok()?
+        .username;
+
+    let latest = open_store(cli).ok()?.latest(&student).ok()??;
+
+    Some(latest.snapshot.schedule)
+}
+
+/// A summary of a snapshot that was just saved.
+#[derive(Clone, Serialize)]
+pub struct SavedSnapshot {
+    id: i64,
+    student: String,
+    taken_at: String,
+    run: u8,
+    classes: usize,
+    assignments: usize,
+}
+
+impl Output for SavedSnapshot {
+    type Row = Self;
+
+    fn kind(&self) -> &'static str {
+        "snapshot"
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        vec![self.clone()]
+    }
+
+    fn print_table(&self) {
+        println!(
+            "Saved snapshot #{} for {} at {} (run {}, {} classes, {} assignments)",
+            self.id, self.student, self.taken_at, self.run, self.classes, self.assignments
+        );
+    }
+}
+
+/// Takes a snapshot and stores it in the database.
+pub fn snapshot(cli: &Cli, client: &Client) -> Result<SavedSnapshot> {
+    let store = open_store(cli)?;
+
+    if cli.verbose > 0 {
+        eprintln!("Taking snapshot for {}", client.username());
+    }
+
+    let snapshot = client.snapshot()?;
+    let id = store.save(&snapshot)?;
+
+    Ok(SavedSnapshot {
+        id,
+        student: snapshot.student,
+        taken_at: snapshot.taken_at.to_rfc3339(),
+        run: snapshot.run,
+        classes: snapshot.classes.len(),
+        assignments: snapshot.classes.iter().map(|c| c.assignments.len()).sum(),
+    })
+}
+
+/// Identifies one of the snapshots a comparison was made between.
+#[derive(Serialize)]
+pub struct SnapshotRef {
+    id: i64,
+    taken_at: String,
+}
+
+impl From<&StoredSnapshot> for SnapshotRef {
+    fn from(stored: &StoredSnapshot) -> Self {
+        Self {
+            id: stored.id,
+            taken_at: stored.snapshot.taken_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ChangesReport {
+    from: SnapshotRef,
+    to: SnapshotRef,
+    changes: GradeChanges,
+}
+
+#[derive(Serialize)]
+pub struct ChangeRow {
+    /// One of `course_added`, `course_dropped`, `average`, `assignment_posted` or `score`.
+    kind: &'static str,
+    course: String,
+    item: Option<String>,
+    old: Option<String>,
+    new: Option<String>,
+}
+
+impl Output for ChangesReport {
+    type Row = ChangeRow;
+
+    fn kind(&self) -> &'static str {
+        "changes"
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        let changes = &self.changes;
+        let text = |value: Option<f32>| value.map(|v| v.to_string());
+
+        let added = changes.courses_added.iter().map(|c| ChangeRow {
+            kind: "course_added",
+            course: c.name().to_owned(),
+            item: None,
+            old: None,
+            new: c.period().map(ToString::to_string),
+        });
+
+        let dropped = changes.courses_dropped.iter().map(|c| ChangeRow {
+            kind: "course_dropped",
+            course: c.name().to_owned(),
+            item: None,
+            old: c.period().map(ToString::to_string),
+            new: None,
+        });
+
+        let averages = changes.averages_changed.iter().map(|c| ChangeRow {
+            kind: "average",
+            course: c.course.clone(),
+            item: None,
+            old: text(c.old),
+            new: text(c.new),
+        });
+
+        let posted = changes.assignments_posted.iter().map(|p| ChangeRow {
+            kind: "assignment_posted",
+            course: p.course.clone(),
+            item: Some(p.assignment.name.clone()),
+            old: None,
+            new: text(p.assignment.score),
+        });
+
+        let scores = changes.scores_changed.iter().map(|c| ChangeRow {
+            kind: "score",
+            course: c.course.clone(),
+            item: Some(c.assignment.name.clone()),
+            old: text(c.old_score),
+            new: text(c.assignment.score),
+        });
+
+        added
+            .chain(dropped)
+            .chain(averages)
+            .chain(posted)
+            .chain(scores)
+            .collect()
+    }
+
+    fn print_table(&self) {
+        println!(
+            "Changes from snapshot #{} ({}) to #{} ({}):",
+            self.from.id, self.from.taken_at, self.to.id, self.to.taken_at
+        );
+
+        if self.changes.is_empty() {
+            println!("\tnothing changed");
+        }
+
+        for line in self.changes.to_string().lines() {
+            println!("\t{line}");
+        }
+    }
+}
+
+/// Finds the snapshot `since` refers to: a snapshot id, a date or an RFC 3339 time.
+fn find_baseline(store: &SnapshotStore, student: &str, since: &str) -> Result<StoredSnapshot> {
+    if let Ok(id) = since.parse::<i64>() {
+        return store
+            .get(id)?
+            .filter(|s| s.snapshot.student == student)
+            .ok_or(anyhow!("no snapshot #{id} for {student}"));
+    }
+
+    let time = match NaiveDate::parse_from_str(since, "%Y-%m-%d") {
+        Ok(date) => date
+            .and_hms_opt(0, 0, 0)
+            .and_then(|t| t.and_local_timezone(Local).earliest())
+            .ok_or(anyhow!("invalid date {since}"))?
+            .to_utc(),
+        Err(_) => DateTime::parse_from_rfc3339(since)
+            .context("--since expects a snapshot id, YYYY-MM-DD or an RFC 3339 time")?
+            .to_utc(),
+    };
+
+    store
+        .latest_before(student, time)?
+        .ok_or(anyhow!("no snapshot for {student} before {since}"))
+}
+
+/// Compares the latest snapshot (or a freshly taken one) against an earlier one.
+pub fn changes(cli: &Cli, since: Option<&str>, fetch: bool) -> Result<ChangesReport> {
+    let store = open_store(cli)?;
+
+    let (student, latest) = if fetch {
+        let client = connect(cli)?;
+        let snapshot = client.snapshot()?;
+        let id = store.save(&snapshot)?;
+
+        (
+            client.username().to_owned(),
+            StoredSnapshot { id, snapshot },
+        )
+    } else {
+        let student =
+            credentials::resolve(&credentials::default_providers(), &cli.profile)?.username;
+        let latest = store.latest(&student)?.ok_or(anyhow!(
+            "no snapshots stored for {student}; run `snapshot` first"
+        ))?;
+
+        (student, latest)
+    };
+
+    let baseline = match since {
+        Some(since) => find_baseline(&store, &student, since)?,
+        None => {
+            let before = latest.snapshot.taken_at - chrono::Duration::nanoseconds(1);
+
+            store
+                .latest_before(&student, before)?
+                .ok_or(anyhow!("only one snapshot stored for {student}"))?
+        }
+    };
+
+    if baseline.id == latest.id {
+        bail!("nothing to compare: snapshot #{} is the latest", latest.id);
+    }
+
+    Ok(ChangesReport {
+        from: SnapshotRef::from(&baseline),
+        to: SnapshotRef::from(&latest),
+        changes: hac::diff(&baseline.snapshot, &latest.snapshot),
+    })
+}
+
+#[derive(Serialize)]
+pub struct TrendsReport {
+    student: String,
+    run: u8,
+    run_end: NaiveDate,
+    classes: Vec<ClassTrend>,
+}
+
+#[derive(Serialize)]
+pub struct TrendRow {
+    course: String,
+    snapshots: usize,
+    average: Option<f32>,
+    /// Points per week.
+    slope: Option<f32>,
+    momentum: Option<f32>,
+    projected: Option<f32>,
+    largest_change_assignment: Option<String>,
+    largest_change: Option<f32>,
+}
+
+impl Output for TrendsReport {
+    type Row = TrendRow;
+
+    fn kind(&self) -> &'static str {
+        "trends"
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        self.classes
+            .iter()
+            .map(|trend| TrendRow {
+                course: trend.course.clone(),
+                snapshots: trend.points.len(),
+                average: trend.latest(),
+                slope: trend.slope.map(|s| s * 7.0),
+                momentum: trend.momentum.map(|m| m * 7.0),
+                projected: trend.projected,
+                largest_change_assignment: trend
+                    .largest_change
+                    .as_ref()
+                    .map(|c| c.assignment.name.clone()),
+                largest_change: trend.largest_change.as_ref().map(|c| c.change),
+            })
+            .collect()
+    }
+
+    fn print_table(&self) {
+        println!(
+            "Run {} trends for {} (projected to {})",
+            self.run, self.student, self.run_end
+        );
+
+        let mut table = Table::new([
+            "Course",
+            "History",
+            "Average",
+            "Per week",
+            "Projected",
+            "Biggest change",
+        ]);
+
+        for trend in &self.classes {
+            let averages = trend.points.iter().map(|p| p.average).collect::<Vec<_>>();
+
+            let grade = |grade: Option<f32>| match grade {
+                Some(grade) => Cell::colored(format!("{grade:.1}"), Color::for_grade(grade)),
+                None => Cell::new("-"),
+            };
+
+            let slope = match trend.slope.map(|s| s * 7.0) {
+                Some(slope) if slope >= 0.5 => {
+                    Cell::colored(format!("↑ {slope:+.1}"), Color::Green)
+                }
+                Some(slope) if slope <= -0.5 => Cell::colored(format!("↓ {slope:+.1}"), Color::Red),
+                Some(slope) => Cell::colored(format!("→ {slope:+.1}"), Color::Dim),
+                None => Cell::new("-"),
+            };
+
+            let largest = match &trend.largest_change {
+                Some(c) => Cell::new(format!("{} ({:+.1})", c.assignment.name, c.change)),
+                None => Cell::new(""),
+            };
+
+            table.row([
+                Cell::new(trend.course.as_str()),
+                Cell::new(sparkline(&averages)),
+                grade(trend.latest()),
+                slope,
+                grade(trend.projected),
+                largest,
+            ]);
+        }
+
+        table.print();
+    }
+}
+
+/// Computes class trends from every stored snapshot of the student's latest run.
+pub fn trends(cli: &Cli, run_end: Option<NaiveDate>) -> Result<TrendsReport> {
+    let store = open_store(cli)?;
+    let student = credentials::resolve(&credentials::default_providers(), &cli.profile)?.username;
+
+    let history = store
+        .history(&student)?
+        .into_iter()
+        .map(|stored| stored.snapshot)
+        .collect::<Vec<_>>();
+
+    let latest = history.last().ok_or(anyhow!(
+        "no snapshots stored for {student}; run `snapshot` first"
+    ))?;
+
+    let run_end = run_end
+        .or_else(|| hac::estimate_run_end(latest))
+        .ok_or(anyhow!("could not guess when the run ends; pass --run-end"))?;
+
+    Ok(TrendsReport {
+        run: latest.run,
+        classes: hac::trends(&history, run_end),
+        student,
+        run_end,
+    })
+}
@@ -0,0 +1,494 @@
+mod batch;
+mod cli;
+#[cfg(feature = "tui")]
+mod dashboard;
+#[cfg(feature = "sqlite")]
+mod history;
+mod output;
+mod serve;
+mod table;
+#[cfg(feature = "sqlite")]
+mod watch;
+
+use std::{
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{anyhow, Context, Result};
+use clap::Parser;
+use hac::{
+    calendar::Calendar,
+    client::Client,
+    credentials::{self, Credentials, EnvProvider, PromptProvider},
+    Adjustments, AppliedAdjustment, Catalog, ClassAssignments, Course, GpaPolicy, GpaScale,
+    GradeSource, GraduationPlan, MergeStrategy, Transcript,
+};
+
+use chrono::{Local, TimeDelta, Utc};
+use cli::{Cli, Command, Format, IcsCommand};
+use output::{
+    Assignments, Attendance, Courses, GpaReport, GpaSource, Graduation, Missing, NowReport,
+    RankReport, Schedule, Teachers, TranscriptReport,
+};
+
+/// Fetches the last posted transcript, followed by this year's grades for every quarter that has
+/// any entered. Each is weighted with `catalog` and then has `adjustments` applied, so that
+/// reweighting rules win over the catalog.
+fn collect_transcripts(
+    client: &Client,
+    catalog: &Catalog,
+    adjustments: &Adjustments,
+    verbose: bool,
+) -> Result<(Vec<Transcript>, Vec<AppliedAdjustment>)> {
+    if verbose {
+        eprintln!("Getting last posted transcript");
+    }
+
+    let mut transcripts = vec![client
+        .get_transcript()
+        .context("could not get transcript")?];
+
+    for quarter in 1..=4 {
+        if verbose {
+            eprintln!("Getting quarter #{quarter} grades");
+        }
+
+        let quarter_grades = client
+            .get_quarter_grades(quarter)
+            .context("could not get quarter grades")?;
+
+        if quarter_grades.entries.is_empty() {
+            if verbose {
+                eprintln!("\tno grades found for quarter... stopping now");
+            }
+
+            break;
+        }
+
+        transcripts.push(quarter_grades);
+    }
+
+    let applied = transcripts
+        .iter_mut()
+        .enumerate()
+        .flat_map(|(i, transcript)| {
+            transcript.reweigh(catalog);
+
+            let source = match i {
+                0 => GradeSource::Transcript,
+                _ => GradeSource::Run,
+            };
+
+            adjustments.apply(transcript, source)
+        })
+        .collect();
+
+    Ok((transcripts, applied))
+}
+
+fn cumulative_gpa(
+    client: &Client,
+    scale: GpaScale,
+    catalog: &Catalog,
+    adjustments: &Adjustments,
+    verbose: bool,
+) -> Result<GpaReport> {
+    let (transcripts, adjustments) = collect_transcripts(client, catalog, adjustments, verbose)?;
+
+    let sources = transcripts
+        .iter()
+        .enumerate()
+        .map(|(i, transcript)| GpaSource {
+            source: match i {
+                0 => "transcript".to_owned(),
+                quarter => format!("quarter {quarter}"),
+            },
+            gpa: transcript.gpa_on(scale),
+            entries: transcript.entries.len(),
+        })
+        .collect();
+
+    let cumulative_transcript = Transcript::combine(&transcripts, MergeStrategy::Average);
+
+    Ok(GpaReport {
+        scale,
+        sources,
+        cumulative_gpa: cumulative_transcript.gpa_on(scale),
+        cumulative_entries: cumulative_transcript.entries.len(),
+        adjustments,
+        excluded: GpaPolicy::bundled().apply(&cumulative_transcript).1,
+    })
+}
+
+/// Projects class rank from the transcript's official rank and the cumulative GPA with this
+/// year's grades. The courses left are the plan's remaining credits, a full-year course per
+/// credit, since the transcripts are merged into one entry per course.
+fn rank_projection(
+    cli: &Cli,
+    client: &Client,
+    spread: f32,
+    plan: Option<&Path>,
+) -> Result<RankReport> {
+    let catalog = load_catalog(cli.catalog.as_deref())?;
+    let (transcripts, adjustments) = collect_transcripts(
+        client,
+        &catalog,
+        &load_adjustments(cli.adjustments.as_deref())?,
+        cli.verbose > 0,
+    )?;
+
+    let progress = load_plan(plan)?.progress(&catalog, &transcripts[0], &client.get_schedule()?);
+    let remaining_courses = progress.remaining().ceil() as usize;
+
+    let projection = hac::project_rank(
+        &client.get_transcript_summary()?,
+        &Transcript::combine(&transcripts[..1], MergeStrategy::Average),
+        &Transcript::combine(&transcripts, MergeStrategy::Average),
+        remaining_courses,
+        spread,
+    )?;
+
+    Ok(RankReport {
+        projection,
+        adjustments,
+    })
+}
+
+/// Asks for credentials, checks that they work and stores them for the profile.
+fn login(profile: &str) -> Result<()> {
+    let credentials =
+        credentials::resolve(&[Box::new(PromptProvider), Box::new(EnvProvider)], profile)?;
+
+    Client::new(&credentials.username, &credentials.password)?;
+
+    save_credentials(profile, &credentials)?;
+    println!(
+        "Logged in as {}; saved as profile `{profile}`",
+        credentials.username
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "keyring")]
+fn save_credentials(profile: &str, credentials: &Credentials) -> Result<()> {
+    credentials::KeyringProvider::new().save(profile, credentials)
+}
+
+#[cfg(not(feature = "keyring"))]
+fn save_credentials(profile: &str, credentials: &Credentials) -> Result<()> {
+    use credentials::ConfigFileProvider;
+
+    let path = ConfigFileProvider::default_path().context("could not find a config directory")?;
+
+    ConfigFileProvider::new(path).save(profile, credentials)
+}
+
+/// Loads the calendar from `path`, or from the default location.
+fn load_calendar(path: Option<&Path>) -> Result<Calendar> {
+    let path = match path {
+        Some(path) => path.to_owned(),
+        None => Calendar::default_path()
+            .filter(|p| p.exists())
+            .ok_or(anyhow!(
+            "no calendar found; create calendar.toml in your config directory or pass --calendar"
+        ))?,
+    };
+
+    Calendar::load(&path)
+}
+
+/// Loads the course catalog with the overrides from `path`, or from the default location if
+/// present.
+fn load_catalog(path: Option<&Path>) -> Result<Catalog> {
+    let path = match path {
+        Some(path) => path.to_owned(),
+        None => match Catalog::default_path().filter(|p| p.exists()) {
+            Some(path) => path,
+            None => return Ok(Catalog::default()),
+        },
+    };
+
+    Catalog::load(&path)
+}
+
+/// Loads the adjustment rules from `path`, or from the default location if present, falling back
+/// to the bundled rules.
+fn load_adjustments(path: Option<&Path>) -> Result<Adjustments> {
+    let path = match path {
+        Some(path) => path.to_owned(),
+        None => match Adjustments::default_path().filter(|p| p.exists()) {
+            Some(path) => path,
+            None => return Ok(Adjustments::default()),
+        },
+    };
+
+    Adjustments::load(&path)
+}
+
+/// Loads the graduation plan from `path`, or from the default location if present, falling back
+/// to the bundled plan.
+fn load_plan(path: Option<&Path>) -> Result<GraduationPlan> {
+    let path = match path {
+        Some(path) => path.to_owned(),
+        None => match GraduationPlan::default_path().filter(|p| p.exists()) {
+            Some(path) => path,
+            None => return Ok(GraduationPlan::default()),
+        },
+    };
+
+    GraduationPlan::load(&path)
+}
+
+/// Weights a transcript with the catalog and applies the adjustment rules to it.
+fn adjust(
+    cli: &Cli,
+    mut transcript: Transcript,
+    source: GradeSource,
+) -> Result<(Transcript, Vec<AppliedAdjustment>)> {
+    transcript.reweigh(&load_catalog(cli.catalog.as_deref())?);
+    let applied = load_adjustments(cli.adjustments.as_deref())?.apply(&mut transcript, source);
+
+    Ok((transcript, applied))
+}
+
+/// Where the schedule last fetched for a profile is kept (eg.
+/// `~/.local/share/hac/schedule-default.json`).
+fn schedule_cache_path(profile: &str) -> Option<PathBuf> {
+    dirs::data_local_dir().map(|dir| dir.join("hac").join(format!("schedule-{profile}.json")))
+}
+
+/// Fetches the schedule from HAC and caches it for [`cached_schedule`]. Failing to write the cache
+/// isn't an error.
+fn fetch_schedule(cli: &Cli, client: &Client) -> Result<Vec<Course>> {
+    let schedule = client.get_schedule()?;
+
+    let cached = schedule_cache_path(&cli.profile)
+        .context("could not find a data directory")
+        .and_then(|path| {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+
+            Ok(std::fs::write(path, serde_json::to_string(&schedule)?)?)
+        });
+
+    if let Err(e) = cached {
+        if cli.verbose > 0 {
+            eprintln!("could not cache the schedule: {e:#}");
+        }
+    }
+
+    Ok(schedule)
+}
+
+/// The schedule without logging into HAC, when possible: the latest stored snapshot's (with the
+/// `sqlite` feature), or else the one cached by the last fetch. Logs in and fetches it when
+/// neither is available, or when `refresh` is set.
+fn cached_schedule(cli: &Cli, refresh: bool) -> Result<Vec<Course>> {
+    if !refresh {
+        #[cfg(feature = "sqlite")]
+        if let Some(schedule) = history::stored_schedule(cli) {
+            return Ok(schedule);
+        }
+
+        let cached = schedule_cache_path(&cli.profile)
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok());
+
+        if let Some(schedule) = cached {
+            return Ok(schedule);
+        }
+    }
+
+    fetch_schedule(cli, &connect(cli)?)
+}
+
+/// The iCalendar feed of upcoming and missing assignments, as of now.
+fn assignment_feed(classes: &[ClassAssignments], remind_hours: &[i64]) -> String {
+    let reminders = remind_hours
+        .iter()
+        .map(|hours| TimeDelta::hours(*hours))
+        .collect::<Vec<_>>();
+
+    hac::ics::assignments(classes, Local::now().date_naive(), &reminders, Utc::now())
+}
+
+/// Writes an iCalendar file to `path`, or to stdout.
+fn write_ics(path: Option<&Path>, ics: &str) -> Result<()> {
+    match path {
+        Some(path) => {
+            std::fs::write(path, ics).with_context(|| format!("could not write {}", path.display()))
+        }
+        None => Ok(io::stdout().write_all(ics.as_bytes())?),
+    }
+}
+
+fn connect(cli: &Cli) -> Result<Client> {
+    let credentials = credentials::resolve(&credentials::default_providers(), &cli.profile)?;
+
+    if cli.verbose > 0 {
+        eprintln!("Logging in as {}", credentials.username);
+    }
+
+    Client::new(&credentials.username, &credentials.password)
+        .context("could not authenticate with hac")
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let verbose = cli.verbose > 0;
+
+    let format = cli.format;
+
+    match &cli.command {
+        Command::Login => login(&cli.profile)?,
+        #[cfg(feature = "sqlite")]
+        Command::Changes { since, fetch } => {
+            output::print(format, &history::changes(&cli, since.as_deref(), *fetch)?)?
+        }
+        #[cfg(feature = "sqlite")]
+        Command::Trends { run_end } => output::print(format, &history::trends(&cli, *run_end)?)?,
+        #[cfg(feature = "sqlite")]
+        Command::Watch(args) => watch::run(&cli, args)?,
+        Command::Batch { accounts, delay } => {
+            let report = batch::run(
+                accounts,
+                Duration::from_secs(*delay),
+                &load_catalog(cli.catalog.as_deref())?,
+                &load_adjustments(cli.adjustments.as_deref())?,
+            )?;
+            output::print(format, &report)?;
+        }
+        Command::Now { calendar, refresh } => {
+            let calendar = load_calendar(calendar.as_deref())?;
+            let now = chrono::Local::now().naive_local();
+
+            output::print(
+                format,
+                &NowReport::new(&calendar, &cached_schedule(&cli, *refresh)?, now),
+            )?
+        }
+        command => {
+            let client = connect(&cli)?;
+
+            match command {
+                Command::Schedule => {
+                    output::print(format, &Schedule(fetch_schedule(&cli, &client)?))?
+                }
+                Command::Courses => {
+                    let catalog = load_catalog(cli.catalog.as_deref())?;
+                    output::print(format, &Courses::new(client.get_schedule()?, &catalog))?
+                }
+                Command::Teachers => output::print(format, &Teachers(client.get_teachers()?))?,
+                Command::Transcript => {
+                    let (transcript, applied) =
+                        adjust(&cli, client.get_transcript()?, GradeSource::Transcript)?;
+
+                    output::print(format, &TranscriptReport::transcript(transcript, applied))?
+                }
+                Command::Grades { run } => {
+                    // the previous run is only needed to show trends in the table
+                    let previous = match format {
+                        Format::Table if *run > 1 => {
+                            let previous = client.get_quarter_grades(run - 1)?;
+                            Some(adjust(&cli, previous, GradeSource::Run)?.0)
+                        }
+                        _ => None,
+                    };
+
+                    let (grades, applied) =
+                        adjust(&cli, client.get_quarter_grades(*run)?, GradeSource::Run)?;
+
+                    output::print(
+                        format,
+                        &TranscriptReport::grades(*run, grades, previous, applied),
+                    )?
+                }
+                Command::Assignments { run } => output::print(
+                    format,
+                    &Assignments {
+                        run: *run,
+                        classes: client.get_assignments(*run)?,
+                    },
+                )?,
+                Command::Missing => {
+                    output::print(format, &Missing(client.get_missing_assignments()?))?
+                }
+                Command::Gpa { scale } => output::print(
+                    format,
+                    &cumulative_gpa(
+                        &client,
+                        (*scale).into(),
+                        &load_catalog(cli.catalog.as_deref())?,
+                        &load_adjustments(cli.adjustments.as_deref())?,
+                        verbose,
+                    )?,
+                )?,
+                #[cfg(feature = "sqlite")]
+                Command::Snapshot => output::print(format, &history::snapshot(&cli, &client)?)?,
+                #[cfg(feature = "tui")]
+                Command::Dashboard => dashboard::run(
+                    &client,
+                    &load_catalog(cli.catalog.as_deref())?,
+                    &load_adjustments(cli.adjustments.as_deref())?,
+                )?,
+                Command::Graduation { plan, official } => {
+                    let progress = if *official {
+                        client.get_graduation_progress()?
+                    } else {
+                        load_plan(plan.as_deref())?.progress(
+                            &load_catalog(cli.catalog.as_deref())?,
+                            &client.get_transcript()?,
+                            &client.get_schedule()?,
+                        )
+                    };
+
+                    output::print(format, &Graduation(progress))?
+                }
+                Command::Rank { spread, plan } => output::print(
+                    format,
+                    &rank_projection(&cli, &client, *spread, plan.as_deref())?,
+                )?,
+                Command::Attendance => {
+                    output::print(format, &Attendance(client.get_attendance()?))?
+                }
+                Command::Ics(IcsCommand::Schedule { calendar, output }) => {
+                    let calendar = load_calendar(calendar.as_deref())?;
+                    let ics = hac::ics::schedule(&calendar, &client.get_schedule()?, Utc::now());
+
+                    write_ics(output.as_deref(), &ics)?
+                }
+                Command::Ics(IcsCommand::Assignments {
+                    output,
+                    remind_hours,
+                }) => {
+                    let (_, classes) = client.get_current_assignments()?;
+                    let ics = assignment_feed(&classes, remind_hours);
+
+                    write_ics(output.as_deref(), &ics)?
+                }
+                Command::Ics(IcsCommand::Serve {
+                    bind,
+                    refresh,
+                    remind_hours,
+                }) => serve::run(
+                    &cli,
+                    client,
+                    bind,
+                    Duration::from_secs(refresh * 60),
+                    remind_hours,
+                )?,
+                #[cfg(feature = "sqlite")]
+                Command::Changes { .. } | Command::Trends { .. } | Command::Watch(_) => {
+                    unreachable!()
+                }
+                Command::Login | Command::Batch { .. } | Command::Now { .. } => unreachable!(),
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,1000 @@
+//! Printing command results in every `--format`.
+//!
+//! The `json` and `toml` formats wrap every result in the same envelope:
+//!
+//! ```json
+//! { "schema_version": 5, "kind": "schedule", "data": { ... } }
+//! ```
+//!
+//! `kind` names the command that produced `data` (eg. `schedule`, `grades` or `batch`), and `data`
+//! is the serialized report type for that command, which in turn embeds the library's models.
+//! [`SCHEMA_VERSION`] is bumped whenever a field is renamed, removed or changes type; new fields
+//! may be added without a bump, so consumers should ignore fields they do not know.
+//!
+//! The `csv` format writes one flat row per item, with a header row.
+
+use std::{collections::BTreeSet, io, iter};
+
+use anyhow::Result;
+use chrono::NaiveDateTime;
+use hac::{
+    calendar::{Calendar, Meeting},
+    AppliedAdjustment, AssignmentStatus, AttendanceDay, Catalog, ClassAssignments, Classification,
+    Course, CourseStatus, Day, ExcludedCourse, ExclusionReason, GpaPolicy, GpaScale, Grade,
+    GraduationProgress, Level, MissingAssignment, Period, RankProjection, TeacherEntry, Transcript,
+    TranscriptEntry,
+};
+use serde::Serialize;
+
+use crate::{
+    cli::Format,
+    table::{self, Cell, Color, Table},
+};
+
+/// The version of the JSON/TOML output schema.
+///
+/// Version 5 parses periods that used to be `{"unknown": ...}`: an `ADV` period is now
+/// `"advisory"`, and a `1-2` period is `{"range": [1, 2]}`.
+pub const SCHEMA_VERSION: u32 = 5;
+
+#[derive(Serialize)]
+struct Envelope<'a, T> {
+    schema_version: u32,
+    kind: &'static str,
+    data: &'a T,
+}
+
+/// A command result that can be printed in every output format.
+pub trait Output: Serialize {
+    type Row: Serialize;
+
+    /// The `kind` recorded in the output envelope.
+    fn kind(&self) -> &'static str;
+
+    /// The flattened rows written by the `csv` format.
+    fn rows(&self) -> Vec<Self::Row>;
+
+    /// Prints the result for humans.
+    fn print_table(&self);
+}
+
+pub fn print<T: Output>(format: Format, output: &T) -> Result<()> {
+    let envelope = Envelope {
+        schema_version: SCHEMA_VERSION,
+        kind: output.kind(),
+        data: output,
+    };
+
+    match format {
+        Format::Table => output.print_table(),
+        Format::Json => println!("{}", serde_json::to_string_pretty(&envelope)?),
+        Format::Toml => print!("{}", toml::to_string(&envelope)?),
+        Format::Csv => {
+            let mut writer = csv::Writer::from_writer(io::stdout());
+
+            for row in output.rows() {
+                writer.serialize(row)?;
+            }
+
+            writer.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct Schedule(pub Vec<Course>);
+
+#[derive(Serialize)]
+pub struct CourseRow {
+    id: String,
+    name: String,
+    /// Every period the course meets in, separated by spaces (eg. `2A 4B`).
+    periods: String,
+    teacher: String,
+    teacher_email: Option<String>,
+    classroom: String,
+    building: Option<String>,
+    status: CourseStatus,
+    marking_periods: String,
+}
+
+impl Output for Schedule {
+    type Row = CourseRow;
+
+    fn kind(&self) -> &'static str {
+        "schedule"
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        self.0
+            .iter()
+            .map(|course| CourseRow {
+                id: course.id().to_owned(),
+                name: course.name().to_owned(),
+                periods: course
+                    .periods()
+                    .iter()
+                    .map(Period::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                teacher: course.teacher().name.clone(),
+                teacher_email: course.teacher().email.clone(),
+                classroom: course.classroom().to_owned(),
+                building: course.building().map(str::to_owned),
+                status: course.status(),
+                marking_periods: course.marking_periods().join(" "),
+            })
+            .collect()
+    }
+
+    /// Prints a grid of periods against A/B days, leaving out dropped courses.
+    fn print_table(&self) {
+        let meetings = self
+            .0
+            .iter()
+            .filter(|c| !c.is_dropped())
+            .flat_map(|c| c.periods().iter().map(move |p| (p, c)))
+            .collect::<Vec<_>>();
+
+        let days = meetings
+            .iter()
+            .map(|(p, _)| p.day())
+            .collect::<BTreeSet<_>>();
+        let numbers = meetings
+            .iter()
+            .map(|(p, _)| p.number())
+            .collect::<BTreeSet<_>>();
+
+        let mut table = Table::new(
+            iter::once("Period".to_owned()).chain(days.iter().map(|day| format!("{day} day"))),
+        );
+
+        for number in numbers {
+            let courses = days.iter().map(|day| {
+                meetings
+                    .iter()
+                    .filter(|(p, _)| p.number() == number && p.day() == *day)
+                    .map(|(p, c)| format!("{} ({})", c.name(), c.classroom_in(p)))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            });
+
+            table.row(iter::once(number.to_string()).chain(courses));
+        }
+
+        table.print();
+    }
+}
+
+/// The schedule's courses, classified by the course catalog.
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct Courses(Vec<ClassifiedCourse>);
+
+#[derive(Clone, Serialize)]
+pub struct ClassifiedCourse {
+    name: String,
+    code: Option<String>,
+    section: Option<String>,
+    semester: Option<u8>,
+    subject: String,
+    level: Level,
+    weightage: f32,
+}
+
+impl Courses {
+    pub fn new(courses: Vec<Course>, catalog: &Catalog) -> Self {
+        let courses = courses
+            .iter()
+            .filter(|c| !c.is_dropped())
+            .map(|course| {
+                let id = course.course_id();
+                let Classification { subject, level } = course.classify(catalog);
+
+                ClassifiedCourse {
+                    name: course.name().to_owned(),
+                    code: id.as_ref().map(|id| id.code.clone()),
+                    section: id.as_ref().and_then(|id| id.section.clone()),
+                    semester: id.and_then(|id| id.semester),
+                    subject,
+                    level,
+                    weightage: level.weightage(),
+                }
+            })
+            .collect();
+
+        Self(courses)
+    }
+}
+
+impl Output for Courses {
+    type Row = ClassifiedCourse;
+
+    fn kind(&self) -> &'static str {
+        "courses"
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        self.0.clone()
+    }
+
+    fn print_table(&self) {
+        let mut table = Table::new(["Code", "Section", "Course", "Subject", "Level", "Weight"]);
+
+        for course in &self.0 {
+            table.row([
+                course.code.clone().unwrap_or_default(),
+                course.section.clone().unwrap_or_default(),
+                course.name.clone(),
+                course.subject.clone(),
+                course.level.to_string(),
+                format!("{:.1}", course.weightage),
+            ]);
+        }
+
+        table.print();
+    }
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct Teachers(pub Vec<TeacherEntry>);
+
+#[derive(Serialize)]
+pub struct TeacherRow {
+    name: String,
+    email: Option<String>,
+    staff_id: Option<String>,
+    /// The courses they teach, separated by `; `.
+    courses: String,
+}
+
+impl Output for Teachers {
+    type Row = TeacherRow;
+
+    fn kind(&self) -> &'static str {
+        "teachers"
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        self.0
+            .iter()
+            .map(|entry| TeacherRow {
+                name: entry.teacher.name.clone(),
+                email: entry.teacher.email.clone(),
+                staff_id: entry.teacher.staff_id.clone(),
+                courses: entry.courses.join("; "),
+            })
+            .collect()
+    }
+
+    fn print_table(&self) {
+        let mut table = Table::new(["Teacher", "Email", "Courses"]);
+
+        for entry in &self.0 {
+            table.row([
+                entry.teacher.name.clone(),
+                entry.teacher.email.clone().unwrap_or_default(),
+                entry.courses.join(", "),
+            ]);
+        }
+
+        table.print();
+    }
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct Graduation(pub GraduationProgress);
+
+#[derive(Serialize)]
+pub struct RequirementRow {
+    requirement: String,
+    required: f32,
+    earned: f32,
+    in_progress: f32,
+    remaining: f32,
+    /// The courses counted, separated by `; `.
+    courses: String,
+}
+
+impl Output for Graduation {
+    type Row = RequirementRow;
+
+    fn kind(&self) -> &'static str {
+        "graduation"
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        self.0
+            .requirements
+            .iter()
+            .map(|r| RequirementRow {
+                requirement: r.name.clone(),
+                required: r.required,
+                earned: r.earned,
+                in_progress: r.in_progress,
+                remaining: r.remaining,
+                courses: r.courses.join("; "),
+            })
+            .collect()
+    }
+
+    fn print_table(&self) {
+        let progress = &self.0;
+        let mut table = Table::new(["Requirement", "Required", "Earned", "In progress", "Left"]);
+
+        for r in &progress.requirements {
+            let left = if r.remaining > 0.0 {
+                Cell::colored(format!("{:.1}", r.remaining), Color::Yellow)
+            } else {
+                Cell::colored("done", Color::Green)
+            };
+
+            table.row([
+                Cell::from(r.name.clone()),
+                Cell::from(format!("{:.1}", r.required)),
+                Cell::from(format!("{:.1}", r.earned)),
+                Cell::from(format!("{:.1}", r.in_progress)),
+                left,
+            ]);
+        }
+
+        table.print();
+
+        println!(
+            "{}: {:.1} of {:.1} credits earned, {:.1} in progress, {:.1} left",
+            progress.plan,
+            progress.earned(),
+            progress.required(),
+            progress.in_progress(),
+            progress.remaining()
+        );
+
+        if progress.surplus > 0.0 {
+            println!("{:.1} credits beyond the plan", progress.surplus);
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct RankReport {
+    #[serde(flatten)]
+    pub projection: RankProjection,
+    /// The adjustment rules that changed or excluded entries in the projected GPA.
+    pub adjustments: Vec<AppliedAdjustment>,
+}
+
+#[derive(Serialize)]
+pub struct ThresholdRow {
+    top_percent: f32,
+    rank: u32,
+    gpa: f32,
+    met: bool,
+    needed_points: Option<f32>,
+    reachable: bool,
+}
+
+impl Output for RankReport {
+    type Row = ThresholdRow;
+
+    fn kind(&self) -> &'static str {
+        "rank"
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        self.projection
+            .thresholds
+            .iter()
+            .map(|t| ThresholdRow {
+                top_percent: t.top_percent,
+                rank: t.rank,
+                gpa: t.gpa,
+                met: t.met,
+                needed_points: t.needed_points,
+                reachable: t.reachable,
+            })
+            .collect()
+    }
+
+    fn print_table(&self) {
+        let projection = &self.projection;
+
+        print_adjustments(&self.adjustments);
+
+        println!(
+            "Official: {:.4} GPA, rank {} of {}",
+            projection.official_gpa, projection.official_rank, projection.class_size
+        );
+        println!(
+            "Projected: {:.4} GPA, rank ~{} of {} (top {:.1}%)",
+            projection.projected_gpa,
+            projection.projected_rank,
+            projection.class_size,
+            projection.projected_top_percent
+        );
+
+        let mut table = Table::new(["Threshold", "Rank", "GPA", "Needed per course"]);
+
+        for t in &projection.thresholds {
+            let needed = match t.needed_points {
+                _ if t.met => Cell::colored("on track", Color::Green),
+                Some(points) if t.reachable => {
+                    Cell::colored(format!("{points:.2} points"), Color::Yellow)
+                }
+                Some(_) => Cell::colored("out of reach", Color::Red),
+                None => Cell::colored("missed", Color::Red),
+            };
+
+            table.row([
+                Cell::from(format!("Top {}%", t.top_percent)),
+                Cell::from(t.rank.to_string()),
+                Cell::from(format!("{:.4}", t.gpa)),
+                needed,
+            ]);
+        }
+
+        table.print();
+
+        println!("Assuming:");
+        for assumption in &projection.assumptions {
+            println!("  - {assumption}");
+        }
+    }
+}
+
+/// Lists the adjustment rules that were applied, so adjusted GPAs can be checked.
+pub fn print_adjustments(adjustments: &[AppliedAdjustment]) {
+    for applied in adjustments {
+        println!(
+            "Adjusted {} ({}): {}, by rule \"{}\"",
+            applied.course, applied.grade, applied.action, applied.rule
+        );
+    }
+}
+
+/// Lists the courses left out of the GPA, and why.
+fn print_excluded(excluded: &[ExcludedCourse]) {
+    for course in excluded {
+        println!(
+            "Not counted: {} ({}): {}",
+            course.course, course.grade, course.reason
+        );
+    }
+}
+
+/// A numeric grade colored by letter band, or a mark (eg. "P") dimmed.
+fn grade_cell(grade: &Grade) -> Cell {
+    match grade.numeric() {
+        Some(numeric) => Cell::colored(grade.to_string(), Color::for_grade(numeric)),
+        None => Cell::colored(grade.to_string(), Color::Dim),
+    }
+}
+
+/// A transcript (or a single run's grades) along with its GPA.
+#[derive(Serialize)]
+pub struct TranscriptReport {
+    #[serde(skip)]
+    kind: &'static str,
+    /// The report card run the grades are from, or `None` for the posted transcript.
+    run: Option<u8>,
+    gpa: f32,
+    entries: Vec<TranscriptEntry>,
+    /// The adjustment rules that changed or excluded entries.
+    adjustments: Vec<AppliedAdjustment>,
+    /// The entries left out of the GPA, with the reason.
+    excluded: Vec<ExcludedCourse>,
+    /// The previous run's grades, used to show trends in the table format.
+    #[serde(skip)]
+    previous: Option<Transcript>,
+}
+
+impl TranscriptReport {
+    pub fn transcript(transcript: Transcript, adjustments: Vec<AppliedAdjustment>) -> Self {
+        Self::new("transcript", None, transcript, None, adjustments)
+    }
+
+    pub fn grades(
+        run: u8,
+        transcript: Transcript,
+        previous: Option<Transcript>,
+        adjustments: Vec<AppliedAdjustment>,
+    ) -> Self {
+        Self::new("grades", Some(run), transcript, previous, adjustments)
+    }
+
+    fn new(
+        kind: &'static str,
+        run: Option<u8>,
+        transcript: Transcript,
+        previous: Option<Transcript>,
+        adjustments: Vec<AppliedAdjustment>,
+    ) -> Self {
+        let (_, excluded) = GpaPolicy::bundled().apply(&transcript);
+
+        Self {
+            kind,
+            run,
+            gpa: transcript.gpa(),
+            entries: transcript.entries,
+            adjustments,
+            excluded,
+            previous,
+        }
+    }
+
+    /// Prints the entries grouped by school year, in the order HAC lists them.
+    fn print_by_year(&self) {
+        let mut years: Vec<(Option<&str>, Table)> = Vec::new();
+
+        for entry in &self.entries {
+            let year = entry.year.as_deref();
+
+            if years.last().map(|(y, _)| *y) != Some(year) {
+                years.push((year, Table::new(["Course", "Grade", "Points"])));
+            }
+
+            let (_, table) = years.last_mut().unwrap();
+
+            table.row([
+                Cell::new(entry.name.as_str()),
+                grade_cell(&entry.grade),
+                Cell::new(
+                    entry
+                        .gpa()
+                        .map(|gpa| format!("{gpa:.1}"))
+                        .unwrap_or_else(|| "-".to_owned()),
+                ),
+            ]);
+        }
+
+        for (year, table) in years {
+            println!(
+                "{}",
+                table::paint(year.unwrap_or("Unknown year"), Color::Blue)
+            );
+            table.print();
+            println!();
+        }
+    }
+
+    /// Prints the entries with an arrow showing the change since the previous run.
+    fn print_with_trends(&self) {
+        let mut table = Table::new(["Course", "Average", "Trend"]);
+
+        for entry in &self.entries {
+            let previous = self
+                .previous
+                .iter()
+                .flat_map(|p| &p.entries)
+                .find(|p| p.name == entry.name);
+
+            let change = previous.and_then(|p| Some(entry.grade.numeric()? - p.grade.numeric()?));
+
+            let trend = match change {
+                None => Cell::new(""),
+                Some(change) if change >= 1.0 => {
+                    Cell::colored(format!("↑ +{change}"), Color::Green)
+                }
+                Some(change) if change <= -1.0 => Cell::colored(format!("↓ {change}"), Color::Red),
+                Some(_) => Cell::colored("→", Color::Dim),
+            };
+
+            table.row([
+                Cell::new(entry.name.as_str()),
+                grade_cell(&entry.grade),
+                trend,
+            ]);
+        }
+
+        table.print();
+    }
+}
+
+#[derive(Serialize)]
+pub struct TranscriptRow {
+    name: String,
+    grade: Grade,
+    weightage: f32,
+    gpa: Option<f32>,
+    /// Why the entry doesn't count towards the GPA, if it doesn't.
+    excluded: Option<ExclusionReason>,
+}
+
+impl Output for TranscriptReport {
+    type Row = TranscriptRow;
+
+    fn kind(&self) -> &'static str {
+        self.kind
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        self.entries
+            .iter()
+            .map(|entry| TranscriptRow {
+                name: entry.name.clone(),
+                grade: entry.grade.clone(),
+                weightage: entry.weightage,
+                gpa: entry.gpa(),
+                excluded: GpaPolicy::bundled().exclusion(entry),
+            })
+            .collect()
+    }
+
+    fn print_table(&self) {
+        match self.run {
+            None => self.print_by_year(),
+            Some(_) => self.print_with_trends(),
+        }
+
+        print_adjustments(&self.adjustments);
+        print_excluded(&self.excluded);
+        println!(
+            "GPA with {} entries: {}",
+            self.entries.len() - self.excluded.len(),
+            self.gpa
+        );
+    }
+}
+
+#[derive(Serialize)]
+pub struct Assignments {
+    pub run: u8,
+    pub classes: Vec<ClassAssignments>,
+}
+
+#[derive(Serialize)]
+pub struct AssignmentRow {
+    course: String,
+    name: String,
+    category: String,
+    date_due: Option<String>,
+    date_assigned: Option<String>,
+    score: Option<f32>,
+    total_points: Option<f32>,
+    status: AssignmentStatus,
+}
+
+impl Output for Assignments {
+    type Row = AssignmentRow;
+
+    fn kind(&self) -> &'static str {
+        "assignments"
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        self.classes
+            .iter()
+            .flat_map(|class| {
+                class.assignments.iter().map(|assignment| AssignmentRow {
+                    course: class.course.clone(),
+                    name: assignment.name.clone(),
+                    category: assignment.category.clone(),
+                    date_due: assignment.date_due.map(|d| d.to_string()),
+                    date_assigned: assignment.date_assigned.map(|d| d.to_string()),
+                    score: assignment.score,
+                    total_points: assignment.total_points,
+                    status: assignment.status,
+                })
+            })
+            .collect()
+    }
+
+    fn print_table(&self) {
+        for class in &self.classes {
+            match class.average {
+                Some(average) => println!("{} ({average})", class.course),
+                None => println!("{}", class.course),
+            }
+
+            for assignment in &class.assignments {
+                let score = match (assignment.score, assignment.total_points) {
+                    (Some(score), Some(total)) => format!("{score}/{total}"),
+                    _ => "-".to_owned(),
+                };
+
+                let due = assignment
+                    .date_due
+                    .map(|d| d.to_string())
+                    .unwrap_or_default();
+
+                println!(
+                    "\t{due}\t{score}\t{} ({})",
+                    assignment.name, assignment.category
+                );
+            }
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct Missing(pub Vec<MissingAssignment>);
+
+#[derive(Serialize)]
+pub struct MissingRow {
+    course: String,
+    name: String,
+    status: AssignmentStatus,
+    date_due: Option<String>,
+    score: Option<f32>,
+    total_points: Option<f32>,
+    impact: Option<f32>,
+}
+
+impl Output for Missing {
+    type Row = MissingRow;
+
+    fn kind(&self) -> &'static str {
+        "missing"
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        self.0
+            .iter()
+            .map(|missing| MissingRow {
+                course: missing.course.clone(),
+                name: missing.assignment.name.clone(),
+                status: missing.assignment.status,
+                date_due: missing.assignment.date_due.map(|d| d.to_string()),
+                score: missing.assignment.score,
+                total_points: missing.assignment.total_points,
+                impact: missing.impact,
+            })
+            .collect()
+    }
+
+    fn print_table(&self) {
+        if self.0.is_empty() {
+            println!("No missing, late or zero assignments");
+            return;
+        }
+
+        let mut table = Table::new(["Course", "Assignment", "Status", "Due", "Score", "Impact"]);
+
+        for missing in &self.0 {
+            let assignment = &missing.assignment;
+
+            let status = match assignment.status {
+                AssignmentStatus::Missing => Cell::colored("missing", Color::Red),
+                AssignmentStatus::Zero => Cell::colored("zero", Color::Red),
+                AssignmentStatus::Late => Cell::colored("late", Color::Yellow),
+                _ => Cell::new(""),
+            };
+
+            let score = match (assignment.score, assignment.total_points) {
+                (Some(score), Some(total)) => format!("{score}/{total}"),
+                (None, Some(total)) => format!("-/{total}"),
+                _ => "-".to_owned(),
+            };
+
+            table.row([
+                Cell::new(missing.course.as_str()),
+                Cell::new(assignment.name.as_str()),
+                status,
+                Cell::new(
+                    assignment
+                        .date_due
+                        .map(|d| d.to_string())
+                        .unwrap_or_default(),
+                ),
+                Cell::new(score),
+                Cell::new(
+                    missing
+                        .impact
+                        .map(|i| format!("+{i:.1}"))
+                        .unwrap_or_default(),
+                ),
+            ]);
+        }
+
+        table.print();
+    }
+}
+
+/// The GPA of each transcript that went into a cumulative GPA.
+#[derive(Serialize)]
+pub struct GpaReport {
+    pub scale: GpaScale,
+    pub sources: Vec<GpaSource>,
+    pub cumulative_gpa: f32,
+    pub cumulative_entries: usize,
+    /// The adjustment rules that changed or excluded entries, in any source.
+    pub adjustments: Vec<AppliedAdjustment>,
+    /// The cumulative entries left out of the GPA, with the reason.
+    pub excluded: Vec<ExcludedCourse>,
+}
+
+#[derive(Serialize)]
+pub struct GpaSource {
+    /// Either `transcript` or `quarter N`.
+    pub source: String,
+    pub gpa: f32,
+    pub entries: usize,
+}
+
+impl Output for GpaReport {
+    type Row = GpaSource;
+
+    fn kind(&self) -> &'static str {
+        "gpa"
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        self.sources
+            .iter()
+            .map(|source| GpaSource {
+                source: source.source.clone(),
+                gpa: source.gpa,
+                entries: source.entries,
+            })
+            .chain([GpaSource {
+                source: "cumulative".to_owned(),
+                gpa: self.cumulative_gpa,
+                entries: self.cumulative_entries,
+            }])
+            .collect()
+    }
+
+    fn print_table(&self) {
+        print_adjustments(&self.adjustments);
+        print_excluded(&self.excluded);
+
+        for source in &self.sources {
+            println!("{} GPA: {}", source.source, source.gpa);
+        }
+
+        println!(
+            "Cumulative GPA with {} total entries: {}",
+            self.cumulative_entries, self.cumulative_gpa
+        );
+    }
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct Attendance(pub Vec<AttendanceDay>);
+
+#[derive(Serialize)]
+pub struct AttendanceRow {
+    date: String,
+    period: Option<String>,
+    description: String,
+}
+
+impl Output for Attendance {
+    type Row = AttendanceRow;
+
+    fn kind(&self) -> &'static str {
+        "attendance"
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        self.0
+            .iter()
+            .flat_map(|day| {
+                day.events.iter().map(|event| AttendanceRow {
+                    date: day.date.to_string(),
+                    period: event.period.clone(),
+                    description: event.description.clone(),
+                })
+            })
+            .collect()
+    }
+
+    fn print_table(&self) {
+        if self.0.is_empty() {
+            println!("No attendance events this month");
+        }
+
+        for day in &self.0 {
+            for event in &day.events {
+                match &event.period {
+                    Some(period) => {
+                        println!("\t{} period {period}: {}", day.date, event.description)
+                    }
+                    None => println!("\t{}: {}", day.date, event.description),
+                }
+            }
+        }
+    }
+}
+
+/// A course meeting, as shown by the `now` command.
+#[derive(Clone, Serialize)]
+pub struct MeetingRow {
+    /// `current` or `next`.
+    kind: &'static str,
+    course: String,
+    period: String,
+    classroom: String,
+    teacher: String,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+}
+
+impl MeetingRow {
+    fn new(kind: &'static str, meeting: &Meeting) -> Self {
+        Self {
+            kind,
+            course: meeting.course.name().to_owned(),
+            period: meeting.period.to_string(),
+            classroom: meeting.course.classroom_in(meeting.period).to_owned(),
+            teacher: meeting.course.teacher_in(meeting.period).name.clone(),
+            start: meeting.start,
+            end: meeting.end,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct NowReport {
+    now: NaiveDateTime,
+    /// Today's rotation day (eg. `A`), if there is school today.
+    day: Option<String>,
+    current: Option<MeetingRow>,
+    next: Option<MeetingRow>,
+}
+
+impl NowReport {
+    pub fn new(calendar: &Calendar, schedule: &[Course], now: NaiveDateTime) -> Self {
+        Self {
+            now,
+            day: calendar.rotation_day(now.date()).map(Day::to_string),
+            current: calendar
+                .current_course(schedule, now)
+                .map(|m| MeetingRow::new("current", &m)),
+            next: calendar
+                .next_course(schedule, now)
+                .map(|m| MeetingRow::new("next", &m)),
+        }
+    }
+}
+
+impl Output for NowReport {
+    type Row = MeetingRow;
+
+    fn kind(&self) -> &'static str {
+        "now"
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        self.current.iter().chain(&self.next).cloned().collect()
+    }
+
+    fn print_table(&self) {
+        match &self.day {
+            Some(day) => println!("Today is {} {day} day", self.now.format("%A")),
+            None => println!("No school today"),
+        }
+
+        match &self.current {
+            Some(m) => println!(
+                "Now: {} in {} until {}",
+                m.course,
+                m.classroom,
+                m.end.format("%-I:%M %p")
+            ),
+            None => println!("Now: no class"),
+        }
+
+        match &self.next {
+            Some(m) if m.start.date() == self.now.date() => println!(
+                "Next: {} in {} at {}",
+                m.course,
+                m.classroom,
+                m.start.format("%-I:%M %p")
+            ),
+            Some(m) => println!(
+                "Next: {} in {} on {}",
+                m.course,
+                m.classroom,
+                m.start.format("%A, %B %-d at %-I:%M %p")
+            ),
+            None => println!("Next: nothing scheduled in the next month"),
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! A tiny HTTP server for the assignments iCalendar feed, so calendar apps can subscribe to it.
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use hac::client::Client;
+
+use crate::{assignment_feed, cli::Cli, connect};
+
+const FEED_PATH: &str = "/assignments.ics";
+
+/// How long a connection may take to send its request (or accept the response), so that an idle
+/// connection (eg. a browser preconnect) can't hold up everyone else.
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The feed, refetched from HAC at most once per refresh interval. While HAC is failing, the last
+/// feed fetched keeps being served.
+struct Feed<'a> {
+    cli: &'a Cli,
+    client: Option<Client>,
+    refresh: Duration,
+    remind_hours: &'a [i64],
+    cached: Option<(Instant, String)>,
+}
+
+impl Feed<'_> {
+    fn get(&mut self) -> Result<&str> {
+        let stale = match &self.cached {
+            Some((fetched, _)) => fetched.elapsed() >= self.refresh,
+            None => true,
+        };
+
+        if stale {
+            match (self.fetch(), &mut self.cached) {
+                (Ok(ics), cached) => *cached = Some((Instant::now(), ics)),
+                (Err(e), Some((fetched, _))) => {
+                    eprintln!("serve: {e:#}; serving the last feed");
+                    // wait a full interval before trying HAC again
+                    *fetched = Instant::now();
+                }
+                (Err(e), None) => return Err(e),
+            }
+        }
+
+        Ok(&self.cached.as_ref().unwrap().1)
+    }
+
+    fn fetch(&mut self) -> Result<String> {
+        let client = match self.client.take() {
+            Some(client) => client,
+            None => connect(self.cli)?,
+        };
+
+        // a failure is usually an expired session, so log in again next time
+        let (_, classes) = client.get_current_assignments()?;
+        self.client = Some(client);
+
+        Ok(assignment_feed(&classes, self.remind_hours))
+    }
+}
+
+fn respond(stream: &mut TcpStream, status: &str, content_type: &str, body: &str) -> Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+
+    Ok(())
+}
+
+fn handle(stream: &mut TcpStream, feed: &mut Feed) -> Result<()> {
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+
+    let mut reader = BufReader::new(&*stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+
+    // skip the headers, so that closing the connection doesn't reset it
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    // calendar apps sometimes add query parameters to bust caches
+    let path = path.split('?').next().unwrap_or("");
+
+    match (method, path) {
+        ("GET", "/" | FEED_PATH) => match feed.get() {
+            Ok(ics) => respond(stream, "200 OK", "text/calendar; charset=utf-8", ics),
+            Err(e) => {
+                eprintln!("serve: {e:#}");
+                respond(
+                    stream,
+                    "502 Bad Gateway",
+                    "text/plain",
+                    "could not fetch assignments from HAC\n",
+                )
+            }
+        },
+        ("GET", _) => respond(stream, "404 Not Found", "text/plain", "not found\n"),
+        _ => respond(
+            stream,
+            "405 Method Not Allowed",
+            "text/plain",
+            "method not allowed\n",
+        ),
+    }
+}
+
+/// Serves the feed until the process is killed, one request at a time.
+pub fn run(
+    cli: &Cli,
+    client: Client,
+    bind: &str,
+    refresh: Duration,
+    remind_hours: &[i64],
+) -> Result<()> {
+    let listener =
+        TcpListener::bind(bind).with_context(|| format!("could not listen on {bind}"))?;
+
+    eprintln!(
+        "Serving assignments at http://{}{FEED_PATH}",
+        listener.local_addr()?
+    );
+
+    let mut feed = Feed {
+        cli,
+        client: Some(client),
+        refresh,
+        remind_hours,
+        cached: None,
+    };
+
+    for stream in listener.incoming() {
+        let result = stream
+            .map_err(anyhow::Error::from)
+            .and_then(|mut stream| handle(&mut stream, &mut feed));
+
+        if let Err(e) = result {
+            eprintln!("serve: {e:#}");
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,195 @@
+//! A small box-drawn table renderer with optional ANSI colors.
+
+use std::{
+    io::{self, IsTerminal},
+    sync::LazyLock,
+};
+
+/// Whether colors should be printed: only when stdout is a terminal and `NO_COLOR` is unset.
+static COLOR: LazyLock<bool> =
+    LazyLock::new(|| io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Dim,
+}
+
+impl Color {
+    fn code(self) -> &'static str {
+        match self {
+            Color::Red => "31",
+            Color::Green => "32",
+            Color::Yellow => "33",
+            Color::Blue => "34",
+            Color::Dim => "2",
+        }
+    }
+
+    /// The color for a numeric grade, by letter band.
+    pub fn for_grade(grade: f32) -> Self {
+        match grade.round() {
+            90.0.. => Color::Green,
+            80.0.. => Color::Blue,
+            70.0.. => Color::Yellow,
+            _ => Color::Red,
+        }
+    }
+}
+
+/// Wraps `text` in the escape codes for `color`, if colors are enabled.
+pub fn paint(text: &str, color: Color) -> String {
+    if *COLOR {
+        format!("\x1b[{}m{text}\x1b[0m", color.code())
+    } else {
+        text.to_owned()
+    }
+}
+
+/// Draws the values as a one line chart, scaled between their minimum and maximum.
+#[cfg(feature = "sqlite")]
+pub fn sparkline(values: &[f32]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let min = values.iter().copied().fold(f32::INFINITY, f32::min);
+    let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+
+    values
+        .iter()
+        .map(|value| {
+            if max > min {
+                let level = (value - min) / (max - min) * (BARS.len() - 1) as f32;
+                BARS[level.round() as usize]
+            } else {
+                BARS[BARS.len() / 2]
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Cell {
+    text: String,
+    color: Option<Color>,
+}
+
+impl Cell {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            color: None,
+        }
+    }
+
+    pub fn colored(text: impl Into<String>, color: Color) -> Self {
+        Self {
+            text: text.into(),
+            color: Some(color),
+        }
+    }
+}
+
+impl From<String> for Cell {
+    fn from(text: String) -> Self {
+        Cell::new(text)
+    }
+}
+
+impl From<&str> for Cell {
+    fn from(text: &str) -> Self {
+        Cell::new(text)
+    }
+}
+
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<Cell>>,
+}
+
+impl Table {
+    pub fn new<S: Into<String>>(headers: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            headers: headers.into_iter().map(Into::into).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn row<C: Into<Cell>>(&mut self, cells: impl IntoIterator<Item = C>) {
+        self.rows.push(cells.into_iter().map(Into::into).collect());
+    }
+
+    pub fn print(&self) {
+        print!("{self}");
+    }
+
+    fn widths(&self) -> Vec<usize> {
+        let mut widths = self
+            .headers
+            .iter()
+            .map(|h| h.chars().count())
+            .collect::<Vec<_>>();
+
+        for row in &self.rows {
+            for (i, cell) in row.iter().enumerate() {
+                let width = cell.text.chars().count();
+
+                match widths.get_mut(i) {
+                    Some(w) => *w = (*w).max(width),
+                    None => widths.push(width),
+                }
+            }
+        }
+
+        widths
+    }
+}
+
+impl std::fmt::Display for Table {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let widths = self.widths();
+
+        let line = |f: &mut std::fmt::Formatter<'_>, left, middle, right| {
+            let segments = widths.iter().map(|w| "─".repeat(w + 2)).collect::<Vec<_>>();
+
+            writeln!(f, "{left}{}{right}", segments.join(middle))
+        };
+
+        let cells = |f: &mut std::fmt::Formatter<'_>, cells: &[Cell]| {
+            write!(f, "│")?;
+
+            for (i, width) in widths.iter().enumerate() {
+                let cell = cells.get(i).cloned().unwrap_or_default();
+                let padding = " ".repeat(width - cell.text.chars().count());
+
+                let text = match cell.color {
+                    Some(color) => paint(&cell.text, color),
+                    None => cell.text,
+                };
+
+                write!(f, " {text}{padding} │")?;
+            }
+
+            writeln!(f)
+        };
+
+        line(f, "┌", "┬", "┐")?;
+        cells(
+            f,
+            &self
+                .headers
+                .iter()
+                .map(|h| Cell::new(h.as_str()))
+                .collect::<Vec<_>>(),
+        )?;
+        line(f, "├", "┼", "┤")?;
+
+        for row in &self.rows {
+            cells(f, row)?;
+        }
+
+        line(f, "└", "┴", "┘")
+    }
+}
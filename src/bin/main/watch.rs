@@ -0,0 +1,148 @@
+//! Polling HAC for grade changes, enabled with the `sqlite` feature.
+
+use std::{thread, time::Duration};
+
+use anyhow::{anyhow, Result};
+use chrono::{Local, Utc};
+use hac::{
+    client::{self, Client},
+    notify::{self, Notifier, NotifierConfig, NtfyNotifier, StdoutNotifier, WebhookNotifier},
+    poll::{PollSchedule, WatchState},
+    store::SnapshotStore,
+};
+
+use crate::{
+    cli::{Cli, WatchArgs},
+    connect,
+    history::open_store,
+};
+
+fn build_notifiers(args: &WatchArgs) -> Result<Vec<Box<dyn Notifier>>> {
+    let config = match &args.notify_config {
+        Some(path) => NotifierConfig::load(path)?,
+        None => match NotifierConfig::default_path().filter(|p| p.exists()) {
+            Some(path) => NotifierConfig::load(&path)?,
+            None => NotifierConfig::default(),
+        },
+    };
+
+    let mut notifiers = config.build()?;
+
+    for url in &args.webhook {
+        notifiers.push(Box::new(WebhookNotifier::new(url)));
+    }
+
+    for url in &args.ntfy {
+        notifiers.push(Box::new(NtfyNotifier::new(url)));
+    }
+
+    if notifiers.is_empty() {
+        notifiers.push(Box::new(StdoutNotifier));
+    }
+
+    Ok(notifiers)
+}
+
+/// Takes a snapshot, notifies about anything that changed since the previous one and stores it.
+/// Returns the number of changes found.
+///
+/// The snapshot is only stored once every notifier has succeeded, so that a failed poll is retried
+/// against the same previous snapshot and its changes are sent again rather than lost.
+fn poll(client: &Client, store: &SnapshotStore, notifiers: &[Box<dyn Notifier>]) -> Result<usize> {
+    let previous = store.latest(client.username())?;
+    let snapshot = client.snapshot()?;
+
+    let changes = previous
+        .map(|previous| hac::diff(&previous.snapshot, &snapshot))
+        .filter(|changes| !changes.is_empty());
+
+    if let Some(changes) = &changes {
+        notify::notify_all(notifiers, &snapshot.student, changes)?;
+    }
+
+    store.save(&snapshot)?;
+
+    Ok(changes.map_or(0, |changes| changes.len()))
+}
+
+fn schedule(args: &WatchArgs) -> PollSchedule {
+    PollSchedule {
+        interval: Duration::from_secs(args.interval * 60),
+        max_backoff: Duration::from_secs(args.max_backoff * 60),
+        jitter: args.jitter,
+        quiet_hours: args.quiet_hours,
+        ..PollSchedule::default()
+    }
+}
+
+fn sleep(delay: Duration, verbose: bool) {
+    if verbose {
+        eprintln!("watch: next poll in {} minutes", delay.as_secs() / 60);
+    }
+
+    thread::sleep(delay);
+}
+
+/// Polls forever. Errors are logged and retried with exponential backoff and a fresh login, since
+/// they are usually an expired HAC session, a network hiccup or HAC being down for maintenance.
+/// The time of the last poll is saved to a state file so that restarting doesn't poll early.
+pub fn run(cli: &Cli, args: &WatchArgs) -> Result<()> {
+    let store = open_store(cli)?;
+    let notifiers = build_notifiers(args)?;
+    let schedule = schedule(args);
+    let verbose = cli.verbose > 0;
+
+    let state_path = match &args.state {
+        Some(path) => path.clone(),
+        None => WatchState::default_path(&cli.profile)
+            .ok_or(anyhow!("no data directory; pass --state"))?,
+    };
+    let mut state = WatchState::load(&state_path)?;
+
+    let resume = state.resume_delay(&schedule, Utc::now());
+    sleep(
+        schedule.after_quiet_hours(Local::now().naive_local(), resume),
+        verbose,
+    );
+
+    let mut rng = rand::thread_rng();
+    let mut client = None;
+
+    loop {
+        let result = match &client {
+            Some(client) => poll(client, &store, &notifiers),
+            None => connect(cli).and_then(|c| poll(client.insert(c), &store, &notifiers)),
+        };
+
+        match result {
+            Ok(changes) => {
+                state.record_success(Utc::now());
+
+                if verbose {
+                    eprintln!("watch: {changes} changes");
+                }
+            }
+            Err(e) => {
+                state.record_failure(Utc::now(), &e);
+                client = None;
+
+                if !client::is_maintenance(&e) {
+                    eprintln!("watch: {e:#}");
+                } else if verbose {
+                    eprintln!("watch: HAC is down for maintenance");
+                }
+            }
+        }
+
+        if let Err(e) = state.save(&state_path) {
+            eprintln!("watch: {e:#}");
+        }
+
+        let delay = schedule.next_delay(
+            state.consecutive_failures,
+            Local::now().naive_local(),
+            &mut rng,
+        );
+        sleep(delay, verbose);
+    }
+}
@@ -0,0 +1,422 @@
+//! The district calendar and bell schedules, for working out which class meets when.
+//!
+//! HAC doesn't publish either, so they are read from a TOML file (see [`Calendar`]):
+//!
+//! ```toml
+//! first_day = "2024-08-14"
+//! last_day = "2025-05-22"
+//! # the day letters school days cycle through, starting on `first_day`
+//! rotation = ["A", "B"]
+//!
+//! # optional; semester courses (eg. marked "S1" in HAC) only meet during their semester
+//! [[semesters]]
+//! start = "2024-08-14"
+//! end = "2024-12-20"
+//!
+//! [[semesters]]
+//! start = "2025-01-07"
+//! end = "2025-05-22"
+//!
+//! [[holidays]]
+//! name = "Thanksgiving"
+//! start = "2024-11-25"
+//! end = "2024-11-29"
+//!
+//! # the first schedule is used unless a special day says otherwise
+//! [[schedules]]
+//! name = "regular"
+//! bells = [
+//!     { period = "1", start = "08:45", end = "10:15" },
+//!     { period = "ADV", start = "10:20", end = "10:50" },
+//!     { period = "2", start = "10:55", end = "12:25" },
+//! ]
+//!
+//! [[schedules]]
+//! name = "early release"
+//! bells = [
+//!     { period = "1", start = "08:45", end = "09:30" },
+//!     { period = "2", start = "09:35", end = "10:20" },
+//! ]
+//!
+//! [[special_days]]
+//! date = "2024-10-11"
+//! schedule = "early release"
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::schedule::{Course, Day, Period, PeriodNumber};
+
+/// How far ahead [`Calendar::next_course`] looks for a school day.
+const LOOKAHEAD_DAYS: i64 = 31;
+
+/// The school year's dates, day rotation and bell schedules.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Calendar {
+    /// The first day of school.
+    pub first_day: NaiveDate,
+    /// The last day of school.
+    pub last_day: NaiveDate,
+    /// The day letters that school days cycle through, starting on `first_day`. Empty for
+    /// campuses without a rotation.
+    #[serde(default = "default_rotation")]
+    pub rotation: Vec<Day>,
+    /// The first and second semesters, in order. Without them, every course is taken to meet
+    /// all year.
+    #[serde(default)]
+    pub semesters: Vec<Semester>,
+    /// Weekdays without school that aren't weekends.
+    #[serde(default)]
+    pub holidays: Vec<Holiday>,
+    /// The bell schedules. The first one is used on every school day that isn't a special day.
+    #[serde(default)]
+    pub schedules: Vec<BellSchedule>,
+    /// Days that use a different bell schedule (eg. early release).
+    #[serde(default)]
+    pub special_days: Vec<SpecialDay>,
+}
+
+fn default_rotation() -> Vec<Day> {
+    vec![Day::A, Day::B]
+}
+
+/// One day, or a range of days, off school.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Holiday {
+    #[serde(default)]
+    pub name: Option<String>,
+    pub start: NaiveDate,
+    /// The last day of the holiday, inclusive. Defaults to `start`.
+    #[serde(default)]
+    pub end: Option<NaiveDate>,
+}
+
+impl Holiday {
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end.unwrap_or(self.start)
+    }
+}
+
+/// The first and last days of a semester, inclusive.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Semester {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+/// The start and end times of each period on a kind of day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BellSchedule {
+    pub name: String,
+    pub bells: Vec<Bell>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bell {
+    /// The period as HAC shows it (eg. `1` or `ADV`).
+    pub period: String,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+/// A day that uses a bell schedule other than the first one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpecialDay {
+    pub date: NaiveDate,
+    /// The name of the bell schedule used that day.
+    pub schedule: String,
+}
+
+/// A course meeting at a specific time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Meeting<'a> {
+    pub course: &'a Course,
+    pub period: &'a Period,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
+impl BellSchedule {
+    /// The start and end of a period, or `None` if the schedule doesn't have it. Ranges span
+    /// from the start of their first period to the end of their last.
+    pub fn times(&self, number: &PeriodNumber) -> Option<(NaiveTime, NaiveTime)> {
+        let bell = |number: &PeriodNumber| {
+            self.bells
+                .iter()
+                .find(|b| b.period.parse::<PeriodNumber>().as_ref() == Ok(number))
+        };
+
+        match number {
+            PeriodNumber::Range(first, last) => Some((
+                bell(&PeriodNumber::Number(*first))?.start,
+                bell(&PeriodNumber::Number(*last))?.end,
+            )),
+            number => bell(number).map(|b| (b.start, b.end)),
+        }
+    }
+}
+
+impl Calendar {
+    /// Loads a calendar from a TOML file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("could not read {}", path.display()))?;
+
+        toml::from_str(&contents).with_context(|| format!("invalid {}", path.display()))
+    }
+
+    /// The default config location (eg. `~/.config/hac/calendar.toml`).
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("hac").join("calendar.toml"))
+    }
+
+    /// Whether there is school on `date`: a weekday during the school year that isn't a holiday.
+    pub fn is_school_day(&self, date: NaiveDate) -> bool {
+        self.first_day <= date
+            && date <= self.last_day
+            && !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+            && !self.holidays.iter().any(|h| h.contains(date))
+    }
+
+    /// The rotation day (eg. A or B) of a school day, or `None` if there is no school or no
+    /// rotation.
+    pub fn rotation_day(&self, date: NaiveDate) -> Option<&Day> {
+        if self.rotation.is_empty() || !self.is_school_day(date) {
+            return None;
+        }
+
+        let school_days_before = self
+            .first_day
+            .iter_days()
+            .take_while(|d| *d < date)
+            .filter(|d| self.is_school_day(*d))
+            .count();
+
+        self.rotation.get(school_days_before % self.rotation.len())
+    }
+
+    /// The semester (1 or 2) `date` falls in, or `None` if no semester contains it.
+    pub fn semester(&self, date: NaiveDate) -> Option<u8> {
+        self.semesters
+            .iter()
+            .position(|s| s.start <= date && date <= s.end)
+            .map(|i| i as u8 + 1)
+    }
+
+    /// The bell schedule used on a school day.
+    pub fn bell_schedule(&self, date: NaiveDate) -> Option<&BellSchedule> {
+        if !self.is_school_day(date) {
+            return None;
+        }
+
+        match self.special_days.iter().find(|s| s.date == date) {
+            Some(special) => self.schedules.iter().find(|s| s.name == special.schedule),
+            None => self.schedules.first(),
+        }
+    }
+
+    /// Every meeting of the (non-dropped) courses on `date`, in order. Semester courses only meet
+    /// during their semester, when the calendar lists semesters.
+    pub fn meetings<'a>(&self, courses: &'a [Course], date: NaiveDate) -> Vec<Meeting<'a>> {
+        let Some(bells) = self.bell_schedule(date) else {
+            return Vec::new();
+        };
+        let rotation = self.rotation_day(date);
+        let semester = self.semester(date);
+
+        let mut meetings = courses
+            .iter()
+            .filter(|course| !course.is_dropped())
+            .filter(|course| semester.is_none_or(|s| course.in_semester(s)))
+            .flat_map(|course| course.periods().iter().map(move |period| (course, period)))
+            .filter(|(_, period)| period.day().meets(rotation, date.weekday()))
+            .filter_map(|(course, period)| {
+                let (start, end) = bells.times(period.number())?;
+
+                Some(Meeting {
+                    course,
+                    period,
+                    start: date.and_time(start),
+                    end: date.and_time(end),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        meetings.sort_by_key(|m| m.start);
+
+        meetings
+    }
+
+    /// The course meeting at `now`, if any.
+    pub fn current_course<'a>(
+        &self,
+        courses: &'a [Course],
+        now: NaiveDateTime,
+    ) -> Option<Meeting<'a>> {
+        self.meetings(courses, now.date())
+            .into_iter()
+            .find(|m| m.start <= now && now < m.end)
+    }
+
+    /// The next course to start after `now`, looking up to a month ahead.
+    pub fn next_course<'a>(
+        &self,
+        courses: &'a [Course],
+        now: NaiveDateTime,
+    ) -> Option<Meeting<'a>> {
+        (0..LOOKAHEAD_DAYS)
+            .map(|days| now.date() + TimeDelta::days(days))
+            .flat_map(|date| self.meetings(courses, date))
+            .find(|m| m.start > now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CALENDAR: &str = r#"
+        first_day = "2024-08-14"
+        last_day = "2025-05-22"
+
+        [[holidays]]
+        start = "2024-09-02"
+
+        [[schedules]]
+        name = "regular"
+        bells = [
+            { period = "1", start = "08:45", end = "10:15" },
+            { period = "ADV", start = "10:20", end = "10:50" },
+            { period = "2", start = "10:55", end = "12:25" },
+        ]
+
+        [[schedules]]
+        name = "early release"
+        bells = [
+            { period = "1", start = "08:45", end = "09:30" },
+            { period = "2", start = "09:35", end = "10:20" },
+        ]
+
+        [[special_days]]
+        date = "2024-09-04"
+        schedule = "early release"
+    "#;
+
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 9, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn current_and_next_course() {
+        let calendar: Calendar = toml::from_str(CALENDAR).unwrap();
+        let html = r#"
+            <table id="plnMain_dgSchedule">
+                <tr class="sg-asp-table-header-row">
+                    <td>Course</td><td>Description</td><td>Periods</td><td>Teacher</td>
+                    <td>Room</td><td>Days</td>
+                </tr>
+                <tr class="sg-asp-table-data-row">
+                    <td>ENG2200A - 3</td><td>English 2 Adv</td><td>1</td><td>Smith</td>
+                    <td>B204</td><td>A</td>
+                </tr>
+                <tr class="sg-asp-table-data-row">
+                    <td>CHM1100A - 1</td><td>Chemistry Adv</td><td>1</td><td>Lee</td>
+                    <td>C110</td><td>B</td>
+                </tr>
+                <tr class="sg-asp-table-data-row">
+                    <td>ADV - 1</td><td>Advisory</td><td>ADV</td><td>Lee</td>
+                    <td>C110</td><td>AB</td>
+                </tr>
+            </table>
+        "#;
+        let courses =
+            crate::schedule::parse_schedule(&scraper::Html::parse_document(html)).unwrap();
+
+        // 2024-08-30 is the 13th school day, so an A day, and 09-02 is a holiday
+        let date = |day| NaiveDate::from_ymd_opt(2024, 8, day).unwrap();
+        assert_eq!(calendar.rotation_day(date(14)), Some(&Day::A));
+        assert_eq!(calendar.rotation_day(date(17)), None);
+        assert_eq!(calendar.rotation_day(date(30)), Some(&Day::A));
+        assert!(!calendar.is_school_day(at(2, 12, 0).date()));
+        assert_eq!(calendar.rotation_day(at(3, 12, 0).date()), Some(&Day::B));
+
+        let current = calendar.current_course(&courses, at(3, 9, 0)).unwrap();
+        assert_eq!(current.course.name(), "Chemistry Adv");
+
+        let next = calendar.next_course(&courses, at(3, 9, 0)).unwrap();
+        assert_eq!(next.course.name(), "Advisory");
+
+        assert!(calendar.current_course(&courses, at(3, 12, 30)).is_none());
+
+        // early release on the 4th, which is an A day
+        let next = calendar.next_course(&courses, at(3, 12, 30)).unwrap();
+        assert_eq!(next.course.name(), "English 2 Adv");
+        assert_eq!(next.end, at(4, 9, 30));
+
+        // the weekend and holiday are skipped
+        let next = calendar
+            .next_course(&courses, date(30).and_hms_opt(13, 0, 0).unwrap())
+            .unwrap();
+        assert_eq!(next.start, at(3, 8, 45));
+    }
+
+    #[test]
+    fn semester_courses() {
+        let mut calendar: Calendar = toml::from_str(CALENDAR).unwrap();
+        calendar.semesters = vec![
+            Semester {
+                start: NaiveDate::from_ymd_opt(2024, 8, 14).unwrap(),
+                end: NaiveDate::from_ymd_opt(2024, 12, 20).unwrap(),
+            },
+            Semester {
+                start: NaiveDate::from_ymd_opt(2025, 1, 7).unwrap(),
+                end: NaiveDate::from_ymd_opt(2025, 5, 22).unwrap(),
+            },
+        ];
+
+        // health and PE share a period slot, a semester each
+        let html = r#"
+            <table id="plnMain_dgSchedule">
+                <tr class="sg-asp-table-header-row">
+                    <td>Course</td><td>Description</td><td>Periods</td><td>Teacher</td>
+                    <td>Room</td><td>Days</td><td>Marking Periods</td>
+                </tr>
+                <tr class="sg-asp-table-data-row">
+                    <td>HLT1100 - 1</td><td>Health</td><td>1</td><td>Lee</td>
+                    <td>C110</td><td>AB</td><td>S1</td>
+                </tr>
+                <tr class="sg-asp-table-data-row">
+                    <td>PE1100 - 1</td><td>Physical Education</td><td>1</td><td>Lee</td>
+                    <td>GYM</td><td>AB</td><td>Q3, Q4</td>
+                </tr>
+            </table>
+        "#;
+        let courses =
+            crate::schedule::parse_schedule(&scraper::Html::parse_document(html)).unwrap();
+
+        let names = |calendar: &Calendar, date: NaiveDate| {
+            calendar
+                .meetings(&courses, date)
+                .iter()
+                .map(|m| m.course.name())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names(&calendar, at(3, 9, 0).date()), ["Health"]);
+        assert_eq!(
+            names(&calendar, NaiveDate::from_ymd_opt(2025, 2, 3).unwrap()),
+            ["Physical Education"]
+        );
+
+        // without semesters, both meet all year
+        calendar.semesters.clear();
+        assert_eq!(names(&calendar, at(3, 9, 0).date()).len(), 2);
+    }
+}
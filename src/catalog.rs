@@ -0,0 +1,596 @@
+//! Course codes and the catalog used to classify courses by subject and level.
+//!
+//! HAC ids look like `ENG2200A - 3`: a course code (`ENG2200A`) followed by the section. The code
+//! starts with a subject prefix (`ENG`) and a course number (`2200`), and may end with a suffix
+//! marking the level (`A` for advanced) and the semester (`S1` or `S2`).
+//!
+//! [`Catalog::default`] is the bundled catalog. Its rules can be extended or overridden with a
+//! TOML file (see [`Catalog::load`]), whose rules are tried before the bundled ones:
+//!
+//! ```toml
+//! # subjects these are "on-level" for, rather than "regular", when no level rule matches
+//! core_subjects = ["Computer Science"]
+//!
+//! [[courses]]
+//! code = "CSC4100"
+//! subject = "Computer Science"
+//! level = "ap"
+//!
+//! [[subjects]]
+//! prefix = "ROB"
+//! subject = "Career and Technical Education"
+//!
+//! [[levels]]
+//! keyword = "Honors"
+//! level = "advanced"
+//! ```
+
+use std::{fmt::Display, sync::LazyLock};
+
+#[cfg(feature = "serde")]
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "serde")]
+use anyhow::{Context, Result};
+
+/// The subject of courses no rule matches.
+const OTHER_SUBJECT: &str = "Other";
+
+/// A HAC course id, split into its parts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CourseId {
+    /// The course code, uppercased and without the semester marker (eg. "ENG2200A").
+    pub code: String,
+    /// The section, if the id has one (eg. "3").
+    pub section: Option<String>,
+    /// The semester (1 or 2) for courses whose code marks one.
+    pub semester: Option<u8>,
+}
+
+impl CourseId {
+    /// Parses an id like `ENG2200A - 3` or `ECO4300S2`. Returns `None` if there is no code.
+    pub fn parse(id: &str) -> Option<Self> {
+        let (code, section) = match id.split_once(" - ") {
+            Some((code, section)) => (code.trim(), Some(section.trim())),
+            None => (id.trim(), None),
+        };
+
+        if code.is_empty() || code.contains(char::is_whitespace) {
+            return None;
+        }
+
+        let mut code = code.to_uppercase();
+        let has_number = code.contains(|c: char| c.is_ascii_digit());
+
+        let semester = match code.len().checked_sub(2).map(|i| &code[i..]) {
+            Some("S1") if has_number => Some(1),
+            Some("S2") if has_number => Some(2),
+            _ => None,
+        };
+
+        if semester.is_some() {
+            code.truncate(code.len() - 2);
+        }
+
+        Some(Self {
+            code,
+            section: section.filter(|s| !s.is_empty()).map(str::to_owned),
+            semester,
+        })
+    }
+
+    /// The letters the code starts with (eg. "ENG").
+    pub fn subject_prefix(&self) -> &str {
+        let end = self
+            .code
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(self.code.len());
+
+        &self.code[..end]
+    }
+
+    /// Whatever follows the course number (eg. "A" for "ENG2200A"), which is empty for most
+    /// courses.
+    pub fn suffix(&self) -> &str {
+        let rest = &self.code[self.subject_prefix().len()..];
+        let start = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+
+        &rest[start..]
+    }
+}
+
+impl Display for CourseId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code)?;
+
+        if let Some(semester) = self.semester {
+            write!(f, "S{semester}")?;
+        }
+
+        if let Some(section) = &self.section {
+            write!(f, " - {section}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// How advanced a course is, which decides its weight in the GPA.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Level {
+    /// A course without levels, like most electives.
+    Regular,
+    /// The standard level of a core course that also has advanced levels.
+    OnLevel,
+    /// Advanced (or Pre-AP) courses.
+    Advanced,
+    /// Advanced Placement.
+    Ap,
+    /// International Baccalaureate.
+    Ib,
+    /// Courses that also earn college credit.
+    DualCredit,
+}
+
+impl Level {
+    /// The grade points a 100 earns on the district's weighted scale.
+    pub fn weightage(self) -> f32 {
+        match self {
+            Level::Regular | Level::OnLevel => 5.0,
+            Level::Advanced => 5.5,
+            Level::Ap | Level::Ib | Level::DualCredit => 6.0,
+        }
+    }
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let level = match self {
+            Level::Regular => "regular",
+            Level::OnLevel => "on-level",
+            Level::Advanced => "Adv",
+            Level::Ap => "AP",
+            Level::Ib => "IB",
+            Level::DualCredit => "dual credit",
+        };
+
+        write!(f, "{level}")
+    }
+}
+
+/// A course's subject area and level.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Classification {
+    pub subject: String,
+    pub level: Level,
+}
+
+/// A course the catalog lists explicitly, which takes precedence over every rule.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CatalogCourse {
+    /// The course code, without the section (eg. "ENG2200A").
+    pub code: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub subject: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub level: Option<Level>,
+}
+
+/// Assigns a subject to courses whose code has the subject prefix `prefix`, or whose name
+/// contains the word(s) `keyword`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SubjectRule {
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub prefix: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub keyword: Option<String>,
+    pub subject: String,
+}
+
+/// Assigns a level to courses whose code has the suffix `suffix`, or whose name contains the
+/// word(s) `keyword`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LevelRule {
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub suffix: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub keyword: Option<String>,
+    pub level: Level,
+}
+
+/// The table courses are classified with. Within the code and name rules the first matching rule
+/// wins; subjects go by code before name, while a level goes by whichever of the two is higher.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Catalog {
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub courses: Vec<CatalogCourse>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub subjects: Vec<SubjectRule>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub levels: Vec<LevelRule>,
+    /// Subjects whose courses are on-level, rather than regular, when no level rule matches.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub core_subjects: Vec<String>,
+}
+
+/// The bundled subject rules, as (code prefixes, name keywords, subject).
+const SUBJECTS: &[(&[&str], &[&str], &str)] = &[
+    (
+        &["CSC", "CS"],
+        &["Computer Science", "Programming"],
+        "Computer Science",
+    ),
+    (
+        &["ENGR", "CTE", "BUS", "AGR", "HSC"],
+        &["Engineering", "Business", "Principles"],
+        "Career and Technical Education",
+    ),
+    (&["ENG", "RDG"], &["English", "Literature"], "English"),
+    (
+        &["MTH", "MAT"],
+        &[
+            "Algebra",
+            "Geometry",
+            "Precalculus",
+            "Calculus",
+            "Statistics",
+            "Math",
+        ],
+        "Mathematics",
+    ),
+    (
+        &["SCI", "BIO", "CHM", "PHY", "AST", "ENV"],
+        &["Biology", "Chemistry", "Physics", "Science", "Astronomy"],
+        "Science",
+    ),
+    (
+        &["SOC", "HIS", "GOV", "ECO", "GEO", "PSY"],
+        &[
+            "History",
+            "Government",
+            "Economics",
+            "Geography",
+            "Psychology",
+        ],
+        "Social Studies",
+    ),
+    (
+        &["SPA", "FRE", "GER", "CHI", "LAT", "JPN", "ASL"],
+        &[
+            "Spanish", "French", "German", "Chinese", "Latin", "Japanese",
+        ],
+        "Languages Other Than English",
+    ),
+    (
+        &["ART", "MUS", "BND", "ORC", "CHO", "THR", "DAN"],
+        &["Art", "Band", "Orchestra", "Choir", "Theatre", "Dance"],
+        "Fine Arts",
+    ),
+    (
+        &["PE", "ATH", "HLT"],
+        &["Athletics", "Health", "Physical Education"],
+        "Physical Education",
+    ),
+];
+
+/// The bundled level rules, as (code suffixes, name keywords, level).
+const LEVELS: &[(&[&str], &[&str], Level)] = &[
+    (&["AP"], &["AP"], Level::Ap),
+    (&["IB"], &["IB"], Level::Ib),
+    (&["D", "DC"], &["DC", "Dual Credit"], Level::DualCredit),
+    (
+        &["A"],
+        &["Adv", "Advanced", "Pre-AP", "GT"],
+        Level::Advanced,
+    ),
+];
+
+const CORE_SUBJECTS: &[&str] = &["English", "Mathematics", "Science", "Social Studies"];
+
+static BUNDLED: LazyLock<Catalog> = LazyLock::new(Catalog::default);
+
+impl Default for Catalog {
+    /// The bundled catalog.
+    fn default() -> Self {
+        fn owned(values: &'static [&'static str]) -> impl Iterator<Item = Option<String>> {
+            values.iter().map(|v| Some((*v).to_owned()))
+        }
+
+        let subjects = SUBJECTS
+            .iter()
+            .flat_map(|(prefixes, keywords, subject)| {
+                let rules = owned(prefixes)
+                    .map(|prefix| (prefix, None))
+                    .chain(owned(keywords).map(|keyword| (None, keyword)));
+
+                rules.map(|(prefix, keyword)| SubjectRule {
+                    prefix,
+                    keyword,
+                    subject: (*subject).to_owned(),
+                })
+            })
+            .collect();
+
+        let levels = LEVELS
+            .iter()
+            .flat_map(|(suffixes, keywords, level)| {
+                let rules = owned(suffixes)
+                    .map(|suffix| (suffix, None))
+                    .chain(owned(keywords).map(|keyword| (None, keyword)));
+
+                rules.map(|(suffix, keyword)| LevelRule {
+                    suffix,
+                    keyword,
+                    level: *level,
+                })
+            })
+            .collect();
+
+        Self {
+            courses: Vec::new(),
+            subjects,
+            levels,
+            core_subjects: CORE_SUBJECTS.iter().map(|s| (*s).to_owned()).collect(),
+        }
+    }
+}
+
+/// Whether `keyword` appears in `name` as whole words, ignoring case. Words are split at anything
+/// but letters, digits and hyphens, so "Calculus (AP)" has the word "AP" but "Pre-AP" doesn't.
+pub(crate) fn has_words(name: &str, keyword: &str) -> bool {
+    fn words(s: &str) -> Vec<&str> {
+        s.split(|c: char| !c.is_alphanumeric() && c != '-')
+            .filter(|w| !w.is_empty())
+            .collect()
+    }
+
+    let (name, keyword) = (words(name), words(keyword));
+
+    !keyword.is_empty()
+        && name.windows(keyword.len()).any(|window| {
+            window
+                .iter()
+                .zip(&keyword)
+                .all(|(a, b)| a.eq_ignore_ascii_case(b))
+        })
+}
+
+impl Catalog {
+    /// The bundled catalog, shared.
+    pub fn bundled() -> &'static Catalog {
+        &BUNDLED
+    }
+
+    /// Loads overrides from a TOML file on top of the bundled catalog.
+    #[cfg(feature = "serde")]
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("could not read {}", path.display()))?;
+        let overrides: Catalog =
+            toml::from_str(&contents).with_context(|| format!("invalid {}", path.display()))?;
+
+        Ok(Self::default().with_overrides(overrides))
+    }
+
+    /// The default config location (eg. `~/.config/hac/catalog.toml`).
+    #[cfg(feature = "serde")]
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("hac").join("catalog.toml"))
+    }
+
+    /// Puts the courses and rules of `overrides` ahead of this catalog's.
+    pub fn with_overrides(mut self, overrides: Catalog) -> Self {
+        self.courses.splice(0..0, overrides.courses);
+        self.subjects.splice(0..0, overrides.subjects);
+        self.levels.splice(0..0, overrides.levels);
+        self.core_subjects.extend(overrides.core_subjects);
+
+        self
+    }
+
+    /// Classifies a course by its id if known, falling back to its name.
+    pub fn classify(&self, id: Option<&CourseId>, name: &str) -> Classification {
+        let listed = id.and_then(|id| {
+            self.courses
+                .iter()
+                .find(|c| c.code.eq_ignore_ascii_case(&id.code))
+        });
+
+        let by_prefix = || {
+            let id = id?;
+            self.subjects
+                .iter()
+                .find(|r| {
+                    r.prefix
+                        .as_ref()
+                        .is_some_and(|p| id.subject_prefix().eq_ignore_ascii_case(p))
+                })
+                .map(|r| r.subject.clone())
+        };
+
+        let by_name = || {
+            self.subjects
+                .iter()
+                .find(|r| r.keyword.as_ref().is_some_and(|k| has_words(name, k)))
+                .map(|r| r.subject.clone())
+        };
+
+        let subject = listed
+            .and_then(|c| c.subject.clone())
+            .or_else(by_prefix)
+            .or_else(by_name)
+            .unwrap_or_else(|| OTHER_SUBJECT.to_owned());
+
+        let by_suffix = || {
+            let id = id?;
+            self.levels
+                .iter()
+                .find(|r| {
+                    r.suffix
+                        .as_ref()
+                        .is_some_and(|s| id.suffix().eq_ignore_ascii_case(s))
+                })
+                .map(|r| r.level)
+        };
+
+        let by_name = || {
+            self.levels
+                .iter()
+                .find(|r| r.keyword.as_ref().is_some_and(|k| has_words(name, k)))
+                .map(|r| r.level)
+        };
+
+        // the suffix only marks advanced courses, so an AP, IB or dual credit course whose code
+        // ends in "A" is still taken at the level its name gives
+        let level = listed
+            .and_then(|c| c.level)
+            .or_else(|| by_suffix().max(by_name()))
+            .unwrap_or_else(|| {
+                if self.core_subjects.contains(&subject) {
+                    Level::OnLevel
+                } else {
+                    Level::Regular
+                }
+            });
+
+        Classification { subject, level }
+    }
+
+    /// Classifies a course from its raw HAC id (eg. "ENG2200A - 3"), if any, and name.
+    pub fn classify_raw(&self, id: Option<&str>, name: &str) -> Classification {
+        self.classify(id.and_then(CourseId::parse).as_ref(), name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn course_ids() {
+        let id = CourseId::parse("ENG2200A - 3").unwrap();
+        assert_eq!(id.code, "ENG2200A");
+        assert_eq!(id.section.as_deref(), Some("3"));
+        assert_eq!(id.semester, None);
+        assert_eq!(id.subject_prefix(), "ENG");
+        assert_eq!(id.suffix(), "A");
+
+        let id = CourseId::parse("eco4300s2 - 12").unwrap();
+        assert_eq!(id.code, "ECO4300");
+        assert_eq!(id.semester, Some(2));
+        assert_eq!(id.suffix(), "");
+        assert_eq!(id.to_string(), "ECO4300S2 - 12");
+
+        assert_eq!(CourseId::parse("  "), None);
+    }
+
+    #[test]
+    fn classification() {
+        let catalog = Catalog::default().with_overrides(Catalog {
+            courses: vec![CatalogCourse {
+                code: "MTH4100".to_owned(),
+                subject: None,
+                level: Some(Level::DualCredit),
+            }],
+            subjects: Vec::new(),
+            levels: Vec::new(),
+            core_subjects: Vec::new(),
+        });
+
+        let classify = |id, name| catalog.classify_raw(id, name);
+
+        let english = classify(Some("ENG2200A - 3"), "English 2");
+        assert_eq!(english.subject, "English");
+        assert_eq!(english.level, Level::Advanced);
+
+        // the override wins over the "AP" in the name
+        let calculus = classify(Some("MTH4100 - 1"), "Calculus BC AP");
+        assert_eq!(calculus.subject, "Mathematics");
+        assert_eq!(calculus.level, Level::DualCredit);
+
+        // without a code, the name decides
+        let biology = classify(None, "AP Biology");
+        assert_eq!(biology.subject, "Science");
+        assert_eq!(biology.level, Level::Ap);
+
+        assert_eq!(
+            classify(Some("CHM1100 - 1"), "Chemistry").level,
+            Level::OnLevel
+        );
+        assert_eq!(classify(None, "Band 2").level, Level::Regular);
+        assert_eq!(classify(None, "Yearbook").subject, OTHER_SUBJECT);
+
+        // the name's AP wins over the advanced suffix
+        assert_eq!(
+            classify(Some("MTH4200A - 1"), "AP Calculus AB").level,
+            Level::Ap
+        );
+
+        // "ENGR" is engineering, not English
+        assert_eq!(
+            classify(Some("ENGR1100 - 1"), "Principles of Engineering").subject,
+            "Career and Technical Education"
+        );
+    }
+
+    #[test]
+    fn weightage_by_name() {
+        // how weightages were worked out before the catalog, from the name alone
+        fn by_substring(name: &str) -> f32 {
+            if name.contains("AP") {
+                6.0
+            } else if name.contains("Adv") {
+                5.5
+            } else {
+                5.0
+            }
+        }
+
+        let names = [
+            "English 2 Adv",
+            "English 3 AP",
+            "AP English Literature",
+            "Calculus BC AP",
+            "AP Calculus AB",
+            "Calculus BC (AP)",
+            "AP Computer Science A",
+            "Chemistry Adv",
+            "Adv Biology",
+            "Advanced Art 2",
+            "World History Adv",
+            "US History AP",
+            "Algebra 2",
+            "Geometry",
+            "Chemistry",
+            "Spanish 3",
+            "Theatre Arts 1",
+            "Band 2",
+            "Athletics",
+            "Principles of Engineering",
+        ];
+
+        for name in names {
+            assert_eq!(
+                Catalog::bundled().classify(None, name).level.weightage(),
+                by_substring(name),
+                "{name}"
+            );
+        }
+
+        // the one deliberate change: Pre-AP is advanced, not AP
+        assert_eq!(
+            Catalog::bundled().classify(None, "Pre-AP Biology").level,
+            Level::Advanced
+        );
+    }
+}
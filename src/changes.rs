@@ -0,0 +1,352 @@
+use std::fmt::Display;
+
+use crate::{
+    assignments::{Assignment, ClassAssignments},
+    schedule::Course,
+    snapshot::Snapshot,
+};
+
+/// Everything that changed between two snapshots of the same student.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GradeChanges {
+    /// Courses on the new schedule that were not on the old one.
+    pub courses_added: Vec<Course>,
+    /// Courses on the old schedule that are no longer on the new one, or that HAC now marks as
+    /// dropped.
+    pub courses_dropped: Vec<Course>,
+    /// Classes whose average moved (including ones that gained or lost an average).
+    pub averages_changed: Vec<AverageChange>,
+    /// Assignments that were not listed before.
+    pub assignments_posted: Vec<PostedAssignment>,
+    /// Assignments whose score was entered, changed or removed.
+    pub scores_changed: Vec<ScoreChange>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AverageChange {
+    pub course: String,
+    pub old: Option<f32>,
+    pub new: Option<f32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PostedAssignment {
+    pub course: String,
+    pub assignment: Assignment,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScoreChange {
+    pub course: String,
+    /// The assignment as it appears in the new snapshot.
+    pub assignment: Assignment,
+    pub old_score: Option<f32>,
+}
+
+impl GradeChanges {
+    /// The number of changes, of every kind.
+    pub fn len(&self) -> usize {
+        self.courses_added.len()
+            + self.courses_dropped.len()
+            + self.averages_changed.len()
+            + self.assignments_posted.len()
+            + self.scores_changed.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.courses_added.is_empty()
+            && self.courses_dropped.is_empty()
+            && self.averages_changed.is_empty()
+            && self.assignments_posted.is_empty()
+            && self.scores_changed.is_empty()
+    }
+}
+
+/// Computes what changed from `old` to `new`.
+///
+/// Courses are matched by their HAC id, classes by name and assignments by name, category and
+/// due date. If the snapshots are from different report card runs, every class in `new` is
+/// compared against an empty class, since the new run starts from scratch.
+pub fn diff(old: &Snapshot, new: &Snapshot) -> GradeChanges {
+    let courses_added = new
+        .schedule
+        .iter()
+        .filter(|course| !old.schedule.iter().any(|c| c.id() == course.id()))
+        .cloned()
+        .collect();
+
+    // HAC keeps dropped courses on the schedule, so a course is dropped once it's gone or marked
+    let courses_dropped = old
+        .schedule
+        .iter()
+        .filter(|course| !course.is_dropped())
+        .filter_map(
+            |course| match new.schedule.iter().find(|c| c.id() == course.id()) {
+                None => Some(course),
+                Some(now) if now.is_dropped() => Some(now),
+                Some(_) => None,
+            },
+        )
+        .cloned()
+        .collect();
+
+    let mut changes = GradeChanges {
+        courses_added,
+        courses_dropped,
+        ..Default::default()
+    };
+
+    let empty = Vec::new();
+    let old_classes = if old.run == new.run {
+        &old.classes
+    } else {
+        &empty
+    };
+
+    for class in &new.classes {
+        let old_class = old_classes.iter().find(|c| c.course == class.course);
+        diff_class(old_class, class, &mut changes);
+    }
+
+    changes
+}
+
+fn diff_class(old: Option<&ClassAssignments>, new: &ClassAssignments, changes: &mut GradeChanges) {
+    let old_average = old.and_then(|c| c.average);
+
+    if old_average != new.average {
+        changes.averages_changed.push(AverageChange {
+            course: new.course.clone(),
+            old: old_average,
+            new: new.average,
+        });
+    }
+
+    let same = |a: &Assignment, b: &Assignment| {
+        a.name == b.name && a.category == b.category && a.date_due == b.date_due
+    };
+
+    for assignment in &new.assignments {
+        let previous = old
+            .iter()
+            .flat_map(|c| &c.assignments)
+            .find(|a| same(a, assignment));
+
+        match previous {
+            None => changes.assignments_posted.push(PostedAssignment {
+                course: new.course.clone(),
+                assignment: assignment.clone(),
+            }),
+            Some(previous) if previous.score != assignment.score => {
+                changes.scores_changed.push(ScoreChange {
+                    course: new.course.clone(),
+                    assignment: assignment.clone(),
+                    old_score: previous.score,
+                })
+            }
+            Some(_) => {}
+        }
+    }
+}
+
+fn score(score: Option<f32>) -> String {
+    score.map_or("-".to_owned(), |s| s.to_string())
+}
+
+impl Display for GradeChanges {
+    /// Lists every change on its own line, eg. "English 2 Adv: Essay scored 87/100".
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for course in &self.courses_added {
+            match course.period() {
+                Some(period) => writeln!(f, "Added to schedule: {} ({period})", course.name())?,
+                None => writeln!(f, "Added to schedule: {}", course.name())?,
+            }
+        }
+
+        for course in &self.courses_dropped {
+            writeln!(f, "Dropped from schedule: {}", course.name())?;
+        }
+
+        for change in &self.averages_changed {
+            writeln!(
+                f,
+                "{}: average {} -> {}",
+                change.course,
+                score(change.old),
+                score(change.new)
+            )?;
+        }
+
+        for posted in &self.assignments_posted {
+            let assignment = &posted.assignment;
+
+            match assignment.score {
+                Some(_) => writeln!(
+                    f,
+                    "{}: {} posted, scored {}/{}",
+                    posted.course,
+                    assignment.name,
+                    score(assignment.score),
+                    score(assignment.total_points)
+                )?,
+                None => writeln!(f, "{}: {} posted", posted.course, assignment.name)?,
+            }
+        }
+
+        for change in &self.scores_changed {
+            writeln!(
+                f,
+                "{}: {} score {} -> {}",
+                change.course,
+                change.assignment.name,
+                score(change.old_score),
+                score(change.assignment.score)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::{assignments::AssignmentStatus, transcript::Transcript};
+
+    fn assignment(name: &str, score: Option<f32>) -> Assignment {
+        Assignment {
+            name: name.to_owned(),
+            category: "Major Grades".to_owned(),
+            date_due: None,
+            date_assigned: None,
+            score,
+            total_points: Some(100.0),
+            status: AssignmentStatus::Normal,
+        }
+    }
+
+    fn snapshot(run: u8, classes: Vec<ClassAssignments>) -> Snapshot {
+        Snapshot {
+            student: "123456".to_owned(),
+            taken_at: Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap(),
+            schedule: Vec::new(),
+            run,
+            classes,
+            transcript: Transcript {
+                entries: Vec::new(),
+            },
+        }
+    }
+
+    fn class(average: Option<f32>, assignments: Vec<Assignment>) -> ClassAssignments {
+        ClassAssignments {
+            course: "English 2 Adv".to_owned(),
+            average,
+            assignments,
+        }
+    }
+
+    #[test]
+    fn assignment_changes() {
+        let old = snapshot(
+            1,
+            vec![class(
+                Some(90.0),
+                vec![assignment("Essay", None), assignment("Quiz", Some(80.0))],
+            )],
+        );
+        let new = snapshot(
+            1,
+            vec![class(
+                Some(92.0),
+                vec![
+                    assignment("Essay", Some(95.0)),
+                    assignment("Quiz", Some(80.0)),
+                    assignment("Project", None),
+                ],
+            )],
+        );
+
+        let changes = diff(&old, &new);
+
+        assert_eq!(
+            changes.averages_changed,
+            vec![AverageChange {
+                course: "English 2 Adv".to_owned(),
+                old: Some(90.0),
+                new: Some(92.0),
+            }]
+        );
+        assert_eq!(changes.scores_changed.len(), 1);
+        assert_eq!(changes.scores_changed[0].assignment.name, "Essay");
+        assert_eq!(changes.scores_changed[0].old_score, None);
+        assert_eq!(changes.assignments_posted.len(), 1);
+        assert_eq!(changes.assignments_posted[0].assignment.name, "Project");
+        assert_eq!(changes.len(), 3);
+
+        assert!(diff(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn new_run_starts_over() {
+        let old = snapshot(
+            1,
+            vec![class(Some(90.0), vec![assignment("Essay", Some(90.0))])],
+        );
+        let new = snapshot(
+            2,
+            vec![class(Some(90.0), vec![assignment("Essay", Some(90.0))])],
+        );
+
+        let changes = diff(&old, &new);
+
+        assert_eq!(changes.averages_changed[0].old, None);
+        assert_eq!(changes.assignments_posted.len(), 1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn course_without_periods() {
+        let course = serde_json::from_str(
+            r#"{"name": "Art 1", "id": "ART1100 - 1", "periods": [],
+                "teacher": {"name": "Lee"}, "classroom": "A101"}"#,
+        )
+        .unwrap();
+
+        let old = snapshot(1, Vec::new());
+        let mut new = snapshot(1, Vec::new());
+        new.schedule.push(course);
+
+        assert_eq!(diff(&old, &new).to_string(), "Added to schedule: Art 1\n");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn course_marked_dropped() {
+        let course = |status: &str| {
+            serde_json::from_str::<Course>(&format!(
+                r#"{{"name": "Art 1", "id": "ART1100 - 1", "periods": [],
+                    "teacher": {{"name": "Lee"}}, "classroom": "A101", "status": "{status}"}}"#
+            ))
+            .unwrap()
+        };
+
+        let mut old = snapshot(1, Vec::new());
+        old.schedule.push(course("active"));
+        let mut new = snapshot(1, Vec::new());
+        new.schedule.push(course("dropped"));
+
+        let changes = diff(&old, &new);
+        assert!(changes.courses_added.is_empty());
+        assert_eq!(changes.courses_dropped, [course("dropped")]);
+
+        // only reported the first time
+        assert!(diff(&new, &new).is_empty());
+    }
+}
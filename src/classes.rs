@@ -1,11 +1,11 @@
 use crate::{
-    selector,
+    client, selector,
     transcript::{Transcript, TranscriptEntry},
 };
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use reqwest::blocking::Client;
-use scraper::Html;
+use scraper::{ElementRef, Html};
 
 const CURRENT_GRADES_PAGE_URL: &str =
     "https://hac.friscoisd.org/HomeAccess/Content/Student/Assignments.aspx";
@@ -25,9 +25,13 @@ fn get_academic_year() -> u32 {
     }
 }
 
-/// Gets the grades entered for the given quarter. If the quarter is not in range [1, 4], then an
-/// empty transcript will be returned.
-pub fn get_quarter_grades(client: &Client, quarter: u8) -> Result<Transcript> {
+/// Fetches the assignments page for the given report card run (quarter) of this academic year.
+/// Fails if the quarter isn't in range [1, 4], rather than fetching a page HAC leaves empty.
+pub(crate) fn get_report_card_run(client: &Client, quarter: u8) -> Result<Html> {
+    if !(1..=4).contains(&quarter) {
+        bail!("there is no quarter {quarter}; quarters run from 1 to 4");
+    }
+
     let year = get_academic_year();
 
     let quarter = format!("{quarter}-{year}");
@@ -35,42 +39,77 @@ pub fn get_quarter_grades(client: &Client, quarter: u8) -> Result<Transcript> {
 
     payload.extend([("ctl00$plnMain$ddlReportCardRuns", quarter.as_str())]);
 
-    let grades_page_resp = client
-        .post(CURRENT_GRADES_PAGE_URL)
-        .form(&payload)
-        .send()?
-        .text()?;
+    let grades_page_resp = client::fetch_page(client.post(CURRENT_GRADES_PAGE_URL).form(&payload))?;
+
+    Ok(Html::parse_document(&grades_page_resp))
+}
+
+/// The header of an `.AssignmentClass` block.
+pub(crate) struct ClassHeader {
+    /// The course's HAC id (eg. "ENG2200A - 3"), if the header has one.
+    pub id: Option<String>,
+    pub title: String,
+    /// `None` if no grade has been entered for the class.
+    pub average: Option<f32>,
+}
+
+/// Reads the course id, title and average from the header of an `.AssignmentClass` block.
+pub(crate) fn parse_class_header(class: ElementRef) -> Result<ClassHeader> {
+    let header = class
+        .select(selector!(".sg-header"))
+        .next()
+        .ok_or(anyhow!("missing class header"))?;
+
+    let mut children = header
+        .select(selector!(":not(button)"))
+        .map(|c| c.text().next().unwrap_or(""));
+
+    let heading = children
+        .next()
+        .map(str::trim)
+        .ok_or(anyhow!("missing title"))?;
+
+    let (id, title) = match heading.split_once("    ") {
+        Some((id, _)) => (
+            Some(id.trim().to_owned()),
+            heading.split("    ").last().unwrap_or(heading),
+        ),
+        None => (None, heading),
+    };
+
+    children.next();
+
+    let average = children
+        .next()
+        .map(|g| {
+            g.chars()
+                .filter(|c| *c == '.' || c.is_ascii_digit())
+                .collect::<String>()
+        })
+        .ok_or(anyhow!("missing grade"))?
+        .parse::<f32>()
+        .ok();
+
+    Ok(ClassHeader {
+        id,
+        title: title.trim().to_owned(),
+        average,
+    })
+}
 
-    let document = Html::parse_document(&grades_page_resp);
+/// Gets the grades entered for the given quarter. Fails if the quarter is not in range [1, 4].
+pub fn get_quarter_grades(client: &Client, quarter: u8) -> Result<Transcript> {
+    let document = get_report_card_run(client, quarter)?;
     let classes = document.select(selector!(".AssignmentClass"));
 
     let mut grades = Vec::new();
 
     for class in classes {
-        let header = class.select(selector!(".sg-header")).next().unwrap();
-
-        let mut children = header
-            .select(selector!(":not(button)"))
-            .map(|c| c.text().next().unwrap_or(""));
-
-        let title = children
-            .next()
-            .and_then(|name| name.trim().split("    ").last())
-            .ok_or(anyhow!("missing title"))?
-            .trim()
-            .to_owned();
-
-        children.next();
-
-        let Ok(grade) = children
-            .next()
-            .map(|g| {
-                g.chars()
-                    .filter(|c| *c == '.' || c.is_ascii_digit())
-                    .collect::<String>()
-            })
-            .ok_or(anyhow!("missing grade"))?
-            .parse::<f32>()
+        let ClassHeader {
+            id,
+            title,
+            average: Some(grade),
+        } = parse_class_header(class)?
         else {
             // no grade has been entered for this class
             continue;
@@ -79,17 +118,10 @@ pub fn get_quarter_grades(client: &Client, quarter: u8) -> Result<Transcript> {
         let grade = grade.round();
 
         if grade != 0.0 {
-            if title.contains("Computer Science A") {
-                //grades.push(TranscriptEntry::new(format!("{title}_duplicate"), grade));
-            }
-
-            let grade = if title.contains("Computer Science A") && grade == 97.0 {
-                100.0
-            } else {
-                grade
-            };
-
-            grades.push(TranscriptEntry::new(title, grade));
+            grades.push(match id {
+                Some(id) => TranscriptEntry::with_id(&id, title, grade),
+                None => TranscriptEntry::new(title, grade),
+            });
         }
     }
 
@@ -1,16 +1,86 @@
+use std::fmt::Display;
+
 use anyhow::{anyhow, Result};
-use reqwest::blocking::ClientBuilder;
+use reqwest::{
+    blocking::{ClientBuilder, RequestBuilder},
+    StatusCode,
+};
 use scraper::Html;
 
 use crate::{
+    assignments::{self, ClassAssignments},
+    attendance::{self, AttendanceDay},
     classes,
+    graduation::{self, GraduationProgress},
+    missing::{self, MissingAssignment},
     schedule::{self, Course},
     selector,
-    transcript::{self, Transcript},
+    snapshot::Snapshot,
+    teachers::{self, TeacherEntry},
+    transcript::{self, Transcript, TranscriptSummary},
 };
 
 const LOGIN_PAGE_URL: &str = "https://hac.friscoisd.org/HomeAccess/Account/LogOn";
 
+/// Phrases in the title or headings of HAC's maintenance and outage pages.
+const MAINTENANCE_PHRASES: [&str; 4] = [
+    "down for maintenance",
+    "scheduled maintenance",
+    "currently unavailable",
+    "service unavailable",
+];
+
+/// The error returned (inside an [`anyhow::Error`]) when HAC serves its maintenance or outage
+/// page instead of the requested one. Use [`is_maintenance`] to check for it.
+#[derive(Debug, Clone)]
+pub struct Maintenance;
+
+impl Display for Maintenance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HAC is down for maintenance")
+    }
+}
+
+impl std::error::Error for Maintenance {}
+
+/// Whether the error was caused by HAC being down for maintenance.
+pub fn is_maintenance(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<Maintenance>().is_some()
+}
+
+/// Sends the request and returns the page's body, failing with [`Maintenance`] if HAC is down.
+pub(crate) fn fetch_page(request: RequestBuilder) -> Result<String> {
+    let resp = request.send()?;
+
+    if resp.status() == StatusCode::SERVICE_UNAVAILABLE {
+        return Err(Maintenance.into());
+    }
+
+    let body = resp.error_for_status()?.text()?;
+
+    if is_maintenance_page(&body) {
+        return Err(Maintenance.into());
+    }
+
+    Ok(body)
+}
+
+/// Whether the page is HAC's maintenance or outage page. Only the title and headings are checked,
+/// since an ordinary page can mention the phrases anywhere (eg. in an assignment name), and the
+/// page is only parsed if a phrase appears at all.
+fn is_maintenance_page(body: &str) -> bool {
+    let lowercase = body.to_lowercase();
+
+    if !MAINTENANCE_PHRASES.iter().any(|p| lowercase.contains(p)) {
+        return false;
+    }
+
+    Html::parse_document(body)
+        .select(selector!("title, h1, h2"))
+        .map(|e| e.text().collect::<String>().to_lowercase())
+        .any(|text| MAINTENANCE_PHRASES.iter().any(|p| text.contains(p)))
+}
+
 /// Represents an open connection to the HAC centers, with cookies
 /// being persisted with each connection. This is the main gateway
 /// for getting data through HAC.
@@ -18,6 +88,8 @@ const LOGIN_PAGE_URL: &str = "https://hac.friscoisd.org/HomeAccess/Account/LogOn
 pub struct Client {
     /// The internal open network connection.
     client: reqwest::blocking::Client,
+    /// The username the client logged in with.
+    username: String,
 }
 
 impl Client {
@@ -25,7 +97,7 @@ impl Client {
     pub fn new(username: &str, password: &str) -> Result<Self> {
         let client = ClientBuilder::new().cookie_store(true).build()?;
 
-        let login_screen_resp = client.get(LOGIN_PAGE_URL).send()?.text()?;
+        let login_screen_resp = fetch_page(client.get(LOGIN_PAGE_URL))?;
         let document = Html::parse_document(&login_screen_resp);
 
         let verification_token = document
@@ -46,25 +118,119 @@ impl Client {
 
         let resp = client.post(LOGIN_PAGE_URL).form(&payload).send()?;
 
+        if resp.status() == StatusCode::SERVICE_UNAVAILABLE {
+            return Err(Maintenance.into());
+        }
+
         if resp.url().as_str() == LOGIN_PAGE_URL {
             Err(anyhow!("failed to login; invalid username or password?"))
         } else {
-            Ok(Self { client })
+            Ok(Self {
+                client,
+                username: username.to_owned(),
+            })
         }
     }
 
+    /// The username this client is logged in as.
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
     /// Returns the schedule (the current classes) a student is enrolled in.
     pub fn get_schedule(&self) -> Result<Vec<Course>> {
         schedule::get_schedule(&self.client)
     }
 
-    /// Returns the most recently published transcript.
+    /// Returns each of the student's teachers, with the courses they teach, sorted by name.
+    pub fn get_teachers(&self) -> Result<Vec<TeacherEntry>> {
+        Ok(teachers::teacher_directory(&self.get_schedule()?))
+    }
+
+    /// Returns the most recently published transcript, as HAC shows it. Use
+    /// [`Adjustments`](crate::Adjustments) to fix up grades before calculating a GPA.
     pub fn get_transcript(&self) -> Result<Transcript> {
         transcript::get_transcript(&self.client)
     }
 
-    /// Returns the grades entered for a particular quarter, this year.
+    /// Returns the cumulative GPA and class rank shown with the posted transcript.
+    pub fn get_transcript_summary(&self) -> Result<TranscriptSummary> {
+        transcript::get_transcript_summary(&self.client)
+    }
+
+    /// Returns the grades entered for a particular quarter (1 to 4), this year, as HAC shows them.
     pub fn get_quarter_grades(&self, quarter: u8) -> Result<Transcript> {
         classes::get_quarter_grades(&self.client, quarter)
     }
+
+    /// Returns every class's assignments for a particular quarter (1 to 4), this year.
+    pub fn get_assignments(&self, quarter: u8) -> Result<Vec<ClassAssignments>> {
+        assignments::get_assignments(&self.client, quarter)
+    }
+
+    /// Returns the latest report card run that has any grades entered, along with every class's
+    /// assignments for it.
+    pub fn get_current_assignments(&self) -> Result<(u8, Vec<ClassAssignments>)> {
+        let mut run = 1;
+        let mut classes = Vec::new();
+
+        for quarter in 1..=4 {
+            let quarter_classes = self.get_assignments(quarter)?;
+
+            if quarter_classes.iter().all(|c| c.average.is_none()) {
+                break;
+            }
+
+            run = quarter;
+            classes = quarter_classes;
+        }
+
+        Ok((run, classes))
+    }
+
+    /// Returns every missing, zero and late assignment in the current report card run, the ones
+    /// costing the most points first.
+    pub fn get_missing_assignments(&self) -> Result<Vec<MissingAssignment>> {
+        let (_, classes) = self.get_current_assignments()?;
+
+        Ok(missing::missing_assignments(&classes))
+    }
+
+    /// Returns the student's progress towards graduation as HAC's Graduation Progress page shows
+    /// it, to cross-check against [`GraduationPlan::progress`](crate::GraduationPlan::progress).
+    pub fn get_graduation_progress(&self) -> Result<GraduationProgress> {
+        graduation::get_graduation_progress(&self.client)
+    }
+
+    /// Returns the attendance events recorded during the current month.
+    pub fn get_attendance(&self) -> Result<Vec<AttendanceDay>> {
+        attendance::get_attendance(&self.client)
+    }
+
+    /// Captures the schedule, transcript and the most recent run's grades and assignments.
+    pub fn snapshot(&self) -> Result<Snapshot> {
+        Snapshot::take(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maintenance_page() {
+        assert!(is_maintenance_page(
+            "<html><body><h1>Home Access Center is down for maintenance</h1>\
+             <p>Please try again later.</p></body></html>"
+        ));
+        assert!(!is_maintenance_page(
+            "<html><body><form><input name=\"LogOnDetails.UserName\"></form></body></html>"
+        ));
+        // an ordinary page that happens to use a phrase
+        assert!(!is_maintenance_page(
+            "<html><head><title>Classwork</title></head><body><table><tr>\
+             <td><a title=\"Service Unavailable Essay\">Service Unavailable Essay</a></td>\
+             </tr></table></body></html>"
+        ));
+    }
 }
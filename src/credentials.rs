@@ -0,0 +1,438 @@
+//! Sources of HAC login credentials.
+//!
+//! The binary looks for credentials in the order returned by [`default_providers`]:
+//!
+//! 1. The `HAC_USERNAME` and `HAC_PASSWORD` environment variables ([`EnvProvider`]).
+//! 2. The OS keyring, when built with the `keyring` feature ([`KeyringProvider`]).
+//! 3. The credentials file in the user's config directory ([`ConfigFileProvider`]), which must
+//!    not be readable by other users.
+//! 4. An interactive prompt with hidden password input ([`PromptProvider`]), only when stdin is
+//!    a terminal.
+//!
+//! The first provider that has credentials for the requested profile wins.
+
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    fs,
+    io::{self, BufRead, IsTerminal, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+/// The profile used when none is given explicitly.
+pub const DEFAULT_PROFILE: &str = "default";
+
+const USERNAME_VAR: &str = "HAC_USERNAME";
+const PASSWORD_VAR: &str = "HAC_PASSWORD";
+
+/// A username and password pair used to log into HAC.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+impl std::fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Credentials")
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
+/// Something that may be able to supply credentials for a profile.
+pub trait CredentialProvider {
+    /// A short human readable name for the provider, used in error messages.
+    fn name(&self) -> &'static str;
+
+    /// Returns the credentials for the given profile, or `None` if this provider has none.
+    fn load(&self, profile: &str) -> Result<Option<Credentials>>;
+}
+
+/// Reads credentials from the `HAC_USERNAME` and `HAC_PASSWORD` environment variables. These
+/// apply to every profile. Setting only one of them is an error.
+pub struct EnvProvider;
+
+impl CredentialProvider for EnvProvider {
+    fn name(&self) -> &'static str {
+        "environment"
+    }
+
+    fn load(&self, _profile: &str) -> Result<Option<Credentials>> {
+        match (std::env::var(USERNAME_VAR), std::env::var(PASSWORD_VAR)) {
+            (Ok(username), Ok(password)) => Ok(Some(Credentials { username, password })),
+            (Ok(_), Err(_)) => bail!("{USERNAME_VAR} is set but {PASSWORD_VAR} is not"),
+            (Err(_), Ok(_)) => bail!("{PASSWORD_VAR} is set but {USERNAME_VAR} is not"),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Reads credentials from an INI style file with one section per profile:
+///
+/// ```text
+/// [default]
+/// username = 123456
+/// password = hunter2
+/// ```
+///
+/// On unix, the file is rejected if it is readable or writable by anyone other than its owner.
+pub struct ConfigFileProvider {
+    path: PathBuf,
+}
+
+impl ConfigFileProvider {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// The credentials file in the user's config directory (eg. `~/.config/hac/credentials`).
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("hac").join("credentials"))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Stores the credentials under `profile`, keeping any other profiles in the file intact.
+    /// The file is created with owner-only permissions.
+    pub fn save(&self, profile: &str, credentials: &Credentials) -> Result<()> {
+        let mut profiles = if self.path.exists() {
+            self.read_profiles()?
+        } else {
+            HashMap::new()
+        };
+
+        profiles.insert(profile.to_owned(), credentials.clone());
+
+        let mut names = profiles.keys().cloned().collect::<Vec<_>>();
+        names.sort();
+
+        let mut contents = String::new();
+
+        for name in names {
+            let credentials = &profiles[&name];
+
+            writeln!(contents, "[{name}]").unwrap();
+            writeln!(contents, "username = {}", credentials.username).unwrap();
+            writeln!(contents, "password = {}", credentials.password).unwrap();
+            writeln!(contents).unwrap();
+        }
+
+        write_private_file(&self.path, contents.trim_end())
+    }
+
+    fn read_profiles(&self) -> Result<HashMap<String, Credentials>> {
+        check_private_permissions(&self.path)?;
+
+        let contents = fs::read_to_string(&self.path)
+            .with_context(|| format!("could not read {}", self.path.display()))?;
+
+        parse_profiles(&contents).with_context(|| format!("in {}", self.path.display()))
+    }
+}
+
+impl CredentialProvider for ConfigFileProvider {
+    fn name(&self) -> &'static str {
+        "config file"
+    }
+
+    fn load(&self, profile: &str) -> Result<Option<Credentials>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+
+        Ok(self.read_profiles()?.remove(profile))
+    }
+}
+
+fn parse_profiles(contents: &str) -> Result<HashMap<String, Credentials>> {
+    let mut sections: Vec<(String, HashMap<&str, &str>)> = Vec::new();
+
+    for (i, line) in contents.lines().enumerate() {
+        // values are kept as written, past the single space `save` puts after the `=`, since
+        // passwords may start or end with spaces
+        let line = line.trim_start();
+
+        if line.trim_end().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(name) = line
+            .trim_end()
+            .strip_prefix('[')
+            .and_then(|l| l.strip_suffix(']'))
+        {
+            sections.push((name.trim().to_owned(), HashMap::new()));
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or(anyhow!("line {}: expected `key = value`", i + 1))?;
+
+        let (_, fields) = sections.last_mut().ok_or(anyhow!(
+            "line {}: value outside of a [profile] section",
+            i + 1
+        ))?;
+
+        fields.insert(key.trim(), value.strip_prefix(' ').unwrap_or(value));
+    }
+
+    sections
+        .into_iter()
+        .map(|(name, fields)| {
+            let field = |key| {
+                fields
+                    .get(key)
+                    .map(|v| v.to_string())
+                    .ok_or(anyhow!("profile `{name}` is missing `{key}`"))
+            };
+
+            let credentials = Credentials {
+                username: field("username")?.trim().to_owned(),
+                password: field("password")?,
+            };
+
+            Ok((name, credentials))
+        })
+        .collect()
+}
+
+/// Asks for the username and (hidden) password on the terminal. Returns `None` when stdin is not
+/// a terminal, so that scripts fail instead of hanging.
+pub struct PromptProvider;
+
+impl CredentialProvider for PromptProvider {
+    fn name(&self) -> &'static str {
+        "prompt"
+    }
+
+    fn load(&self, profile: &str) -> Result<Option<Credentials>> {
+        if !io::stdin().is_terminal() {
+            return Ok(None);
+        }
+
+        eprint!("HAC username ({profile}): ");
+        io::stderr().flush()?;
+
+        let mut username = String::new();
+        io::stdin().lock().read_line(&mut username)?;
+
+        let password = rpassword::prompt_password("HAC password: ")?;
+
+        Ok(Some(Credentials {
+            username: username.trim().to_owned(),
+            password,
+        }))
+    }
+}
+
+/// Stores credentials in the OS keyring (Keychain, Windows Credential Manager or the Secret
+/// Service on Linux) under the service `hac`, with the profile as the account name. If no keyring
+/// is reachable, which is common on headless Linux machines, a private file in the user's data
+/// directory is used instead.
+#[cfg(feature = "keyring")]
+pub struct KeyringProvider {
+    /// `None` when there is no data directory to keep the file in.
+    fallback: Option<ConfigFileProvider>,
+}
+
+#[cfg(feature = "keyring")]
+impl KeyringProvider {
+    const SERVICE: &'static str = "hac";
+
+    pub fn new() -> Self {
+        let fallback = dirs::data_local_dir()
+            .map(|dir| ConfigFileProvider::new(dir.join("hac").join("keyring")));
+
+        Self { fallback }
+    }
+
+    fn fallback(&self) -> Result<&ConfigFileProvider> {
+        self.fallback.as_ref().ok_or(anyhow!(
+            "no keyring is available and there is no data directory"
+        ))
+    }
+
+    /// Stores the credentials for `profile`, in the keyring if possible and the fallback file
+    /// otherwise.
+    pub fn save(&self, profile: &str, credentials: &Credentials) -> Result<()> {
+        let secret = format!("{}:{}", credentials.username, credentials.password);
+
+        match keyring::Entry::new(Self::SERVICE, profile).and_then(|e| e.set_password(&secret)) {
+            Ok(()) => Ok(()),
+            Err(e) if Self::is_unavailable(&e) => self.fallback()?.save(profile, credentials),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn is_unavailable(error: &keyring::Error) -> bool {
+        matches!(
+            error,
+            keyring::Error::NoStorageAccess(_) | keyring::Error::PlatformFailure(_)
+        )
+    }
+}
+
+#[cfg(feature = "keyring")]
+impl Default for KeyringProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "keyring")]
+impl CredentialProvider for KeyringProvider {
+    fn name(&self) -> &'static str {
+        "keyring"
+    }
+
+    fn load(&self, profile: &str) -> Result<Option<Credentials>> {
+        let secret =
+            match keyring::Entry::new(Self::SERVICE, profile).and_then(|e| e.get_password()) {
+                Ok(secret) => Some(secret),
+                Err(keyring::Error::NoEntry) => None,
+                Err(e) if Self::is_unavailable(&e) => None,
+                Err(e) => return Err(e.into()),
+            };
+
+        let Some(secret) = secret else {
+            return match &self.fallback {
+                Some(fallback) => fallback.load(profile),
+                None => Ok(None),
+            };
+        };
+
+        let (username, password) = secret
+            .split_once(':')
+            .ok_or(anyhow!("malformed keyring entry for profile `{profile}`"))?;
+
+        Ok(Some(Credentials {
+            username: username.to_owned(),
+            password: password.to_owned(),
+        }))
+    }
+}
+
+/// The providers consulted by [`resolve`], in the documented order.
+pub fn default_providers() -> Vec<Box<dyn CredentialProvider>> {
+    let mut providers: Vec<Box<dyn CredentialProvider>> = vec![Box::new(EnvProvider)];
+
+    #[cfg(feature = "keyring")]
+    providers.push(Box::new(KeyringProvider::new()));
+
+    if let Some(path) = ConfigFileProvider::default_path() {
+        providers.push(Box::new(ConfigFileProvider::new(path)));
+    }
+
+    providers.push(Box::new(PromptProvider));
+
+    providers
+}
+
+/// Returns the credentials from the first provider that has them for `profile`.
+pub fn resolve(providers: &[Box<dyn CredentialProvider>], profile: &str) -> Result<Credentials> {
+    for provider in providers {
+        let found = provider
+            .load(profile)
+            .with_context(|| format!("reading credentials from {}", provider.name()))?;
+
+        if let Some(credentials) = found {
+            return Ok(credentials);
+        }
+    }
+
+    let tried = providers
+        .iter()
+        .map(|p| p.name())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    bail!("no credentials found for profile `{profile}` (tried {tried})")
+}
+
+#[cfg(unix)]
+fn check_private_permissions(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = fs::metadata(path)?.permissions().mode();
+
+    if mode & 0o077 != 0 {
+        bail!(
+            "{} is accessible by other users (mode {:o}); run `chmod 600` on it",
+            path.display(),
+            mode & 0o777
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_private_permissions(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+fn write_private_file(path: &Path, contents: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+        options.mode(0o600);
+
+        // `mode` only applies to newly created files
+        if path.exists() {
+            fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+        }
+    }
+
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("could not write {}", path.display()))?;
+
+    writeln!(file, "{contents}")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_file_profiles() {
+        let profiles = parse_profiles(
+            "# study group\n[default]\nusername = 123456\npassword = a=b c\n\n[sibling]\nusername=654321\npassword=pw\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            profiles["default"],
+            Credentials {
+                username: "123456".to_owned(),
+                password: "a=b c".to_owned(),
+            }
+        );
+        assert_eq!(profiles["sibling"].username, "654321");
+
+        // passwords are kept verbatim, spaces and all
+        let spaced = parse_profiles("[default]\nusername = 123456 \npassword =  pw \n").unwrap();
+        assert_eq!(spaced["default"].username, "123456");
+        assert_eq!(spaced["default"].password, " pw ");
+
+        assert!(parse_profiles("username = 123456").is_err());
+        assert!(parse_profiles("[default]\nusername = 123456").is_err());
+    }
+}
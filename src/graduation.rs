@@ -0,0 +1,582 @@
+//! Graduation requirements, and progress towards them from the transcript and schedule.
+//!
+//! [`GraduationPlan::default`] is the Texas Foundation High School Program with an endorsement.
+//! Other plans can be loaded from a TOML file (see [`GraduationPlan::load`]):
+//!
+//! ```toml
+//! name = "Foundation"
+//!
+//! # checked in order; a course counts towards the first requirement it matches that still needs
+//! # credits, and anything left over counts as an elective
+//! [[requirements]]
+//! name = "English"
+//! credits = 4
+//! subjects = ["English"]
+//!
+//! [[requirements]]
+//! name = "Speech"
+//! credits = 0.5
+//! keywords = ["Professional Communications", "Speech"]
+//!
+//! [[requirements]]
+//! name = "Electives"
+//! credits = 5.5
+//! electives = true
+//! ```
+//!
+//! HAC's own view of the student's progress, from the Graduation Progress page, is available from
+//! [`Client::get_graduation_progress`](crate::client::Client::get_graduation_progress) to
+//! cross-check against.
+
+#[cfg(feature = "serde")]
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "serde")]
+use anyhow::Context;
+use anyhow::{anyhow, Result};
+use reqwest::blocking::Client;
+use scraper::Html;
+
+use crate::{
+    catalog::{self, Catalog, CourseId},
+    client, html,
+    identity::CourseIdentity,
+    policy::{ExclusionReason, GpaPolicy},
+    schedule::Course,
+    selector,
+    transcript::Transcript,
+};
+
+const GRADUATION_PROGRESS_PAGE_URL: &str =
+    "https://hac.friscoisd.org/HomeAccess/Content/Student/GraduationProgress.aspx";
+
+/// The credits a transcript entry earns when the transcript doesn't list them: HAC lists each
+/// semester separately.
+const SEMESTER_CREDITS: f32 = 0.5;
+
+/// A group of courses the plan requires some number of credits in.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Requirement {
+    pub name: String,
+    pub credits: f32,
+    /// Catalog subject areas (see [`Catalog`]) whose courses count.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub subjects: Vec<String>,
+    /// Words in a course's name that make it count, for requirements narrower than a subject
+    /// (eg. "Health").
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub keywords: Vec<String>,
+    /// Course codes that count.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub codes: Vec<String>,
+    /// Whether any course counts. Courses only count here once every other requirement they
+    /// match is full.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub electives: bool,
+}
+
+impl Requirement {
+    fn matches(&self, code: Option<&CourseId>, name: &str, subject: &str) -> bool {
+        self.subjects.iter().any(|s| s == subject)
+            || self.keywords.iter().any(|k| catalog::has_words(name, k))
+            || code.is_some_and(|code| {
+                self.codes
+                    .iter()
+                    .filter_map(|c| CourseId::parse(c))
+                    .any(|c| c.code == code.code)
+            })
+    }
+}
+
+/// A graduation plan: the credits required in each area.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GraduationPlan {
+    pub name: String,
+    pub requirements: Vec<Requirement>,
+}
+
+/// The bundled requirements, as (name, credits, subjects, keywords).
+const FOUNDATION_ENDORSEMENT: &[(&str, f32, &[&str], &[&str])] = &[
+    ("English", 4.0, &["English"], &[]),
+    ("Mathematics", 4.0, &["Mathematics"], &[]),
+    ("Science", 4.0, &["Science"], &[]),
+    ("Social Studies", 3.0, &["Social Studies"], &[]),
+    (
+        "Languages Other Than English",
+        2.0,
+        &["Languages Other Than English"],
+        &[],
+    ),
+    ("Fine Arts", 1.0, &["Fine Arts"], &[]),
+    ("Health", 0.5, &[], &["Health"]),
+    ("Physical Education", 1.0, &["Physical Education"], &[]),
+    (
+        "Speech",
+        0.5,
+        &[],
+        &[
+            "Professional Communications",
+            "Speech",
+            "Communication Applications",
+        ],
+    ),
+];
+
+/// The credits the Foundation plan with an endorsement requires in total.
+const FOUNDATION_ENDORSEMENT_TOTAL: f32 = 26.0;
+
+impl Default for GraduationPlan {
+    /// The Texas Foundation High School Program with an endorsement (26 credits).
+    fn default() -> Self {
+        let mut requirements = FOUNDATION_ENDORSEMENT
+            .iter()
+            .map(|(name, credits, subjects, keywords)| Requirement {
+                name: (*name).to_owned(),
+                credits: *credits,
+                subjects: subjects.iter().map(|s| (*s).to_owned()).collect(),
+                keywords: keywords.iter().map(|k| (*k).to_owned()).collect(),
+                codes: Vec::new(),
+                electives: false,
+            })
+            .collect::<Vec<_>>();
+
+        let required = requirements.iter().map(|r| r.credits).sum::<f32>();
+
+        requirements.push(Requirement {
+            name: "Electives".to_owned(),
+            credits: FOUNDATION_ENDORSEMENT_TOTAL - required,
+            subjects: Vec::new(),
+            keywords: Vec::new(),
+            codes: Vec::new(),
+            electives: true,
+        });
+
+        Self {
+            name: "Foundation + Endorsement".to_owned(),
+            requirements,
+        }
+    }
+}
+
+/// Progress towards one requirement.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RequirementProgress {
+    pub name: String,
+    pub required: f32,
+    /// Credits earned on the transcript.
+    pub earned: f32,
+    /// Credits from courses on the current schedule.
+    pub in_progress: f32,
+    /// Credits still needed once the courses in progress are passed.
+    pub remaining: f32,
+    /// The courses counted, earned ones first.
+    pub courses: Vec<String>,
+}
+
+/// Progress towards every requirement of a plan.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GraduationProgress {
+    pub plan: String,
+    pub requirements: Vec<RequirementProgress>,
+    /// Credits that didn't fit any requirement (eg. once electives are full).
+    pub surplus: f32,
+}
+
+impl GraduationProgress {
+    pub fn required(&self) -> f32 {
+        self.requirements.iter().map(|r| r.required).sum()
+    }
+
+    pub fn earned(&self) -> f32 {
+        self.requirements.iter().map(|r| r.earned).sum()
+    }
+
+    pub fn in_progress(&self) -> f32 {
+        self.requirements.iter().map(|r| r.in_progress).sum()
+    }
+
+    pub fn remaining(&self) -> f32 {
+        self.requirements.iter().map(|r| r.remaining).sum()
+    }
+}
+
+/// The credits a course on the schedule has yet to earn: half a credit for each semester its
+/// marking periods fall in (or a full credit if HAC doesn't list them), less the semesters of it
+/// already on the transcript, passed or not.
+fn scheduled_credits(course: &Course, transcript: &Transcript) -> f32 {
+    let semesters = [1, 2]
+        .into_iter()
+        .filter(|s| course.in_semester(*s))
+        .count();
+
+    let identity = CourseIdentity::new(Some(course.id()), course.name());
+    let posted = transcript
+        .entries
+        .iter()
+        .filter(|e| identity.matches(&CourseIdentity::new(e.code.as_deref(), &e.name)))
+        .map(|e| e.credits.unwrap_or(SEMESTER_CREDITS))
+        .sum::<f32>();
+
+    (semesters as f32 * SEMESTER_CREDITS - posted).max(0.0)
+}
+
+impl GraduationPlan {
+    /// Loads a plan from a TOML file.
+    #[cfg(feature = "serde")]
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("could not read {}", path.display()))?;
+
+        toml::from_str(&contents).with_context(|| format!("invalid {}", path.display()))
+    }
+
+    /// The default config location (eg. `~/.config/hac/graduation.toml`).
+    #[cfg(feature = "serde")]
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("hac").join("graduation.toml"))
+    }
+
+    /// Works out progress from the posted transcript and the current schedule. Passed courses
+    /// earn the credits the transcript lists (or half a credit per entry if it doesn't), failed
+    /// and local-credit courses earn nothing, and every semester of a course on the schedule that
+    /// isn't dropped or already posted is assumed to be passed. The transcript is taken to only
+    /// list the courses on the schedule for this year's semesters, ie. courses aren't repeated.
+    pub fn progress(
+        &self,
+        catalog: &Catalog,
+        transcript: &Transcript,
+        schedule: &[Course],
+    ) -> GraduationProgress {
+        let mut progress = self
+            .requirements
+            .iter()
+            .map(|r| RequirementProgress {
+                name: r.name.clone(),
+                required: r.credits,
+                earned: 0.0,
+                in_progress: 0.0,
+                remaining: r.credits,
+                courses: Vec::new(),
+            })
+            .collect::<Vec<_>>();
+        let mut surplus = 0.0;
+
+        let earned = transcript
+            .entries
+            .iter()
+            .filter(|e| e.grade.is_passing())
+            .filter(|e| GpaPolicy::bundled().exclusion(e) != Some(ExclusionReason::LocalCredit))
+            .map(|e| {
+                let id = e.code.as_deref().and_then(CourseId::parse);
+                let credits = e.credits.unwrap_or(SEMESTER_CREDITS);
+
+                (id, e.name.as_str(), credits, false)
+            });
+
+        let scheduled = schedule.iter().filter(|c| !c.is_dropped()).map(|c| {
+            let credits = scheduled_credits(c, transcript);
+            (c.course_id(), c.name(), credits, true)
+        });
+
+        for (id, name, mut credits, in_progress) in earned.chain(scheduled) {
+            if credits <= 0.0 {
+                continue;
+            }
+
+            let subject = catalog.classify(id.as_ref(), name).subject;
+
+            // fill the specific requirements first, then electives, and split courses between
+            // requirements when one fills up part way through
+            let specific = self
+                .requirements
+                .iter()
+                .enumerate()
+                .filter(|(_, r)| !r.electives && r.matches(id.as_ref(), name, &subject));
+            let electives = self
+                .requirements
+                .iter()
+                .enumerate()
+                .filter(|(_, r)| r.electives);
+
+            for (i, _) in specific.chain(electives) {
+                let requirement = &mut progress[i];
+                let counted = credits.min(requirement.remaining);
+
+                if counted <= 0.0 {
+                    continue;
+                }
+
+                if in_progress {
+                    requirement.in_progress += counted;
+                } else {
+                    requirement.earned += counted;
+                }
+                requirement.remaining -= counted;
+
+                if !requirement.courses.iter().any(|c| c == name) {
+                    requirement.courses.push(name.to_owned());
+                }
+
+                credits -= counted;
+                if credits <= 0.0 {
+                    break;
+                }
+            }
+
+            surplus += credits;
+        }
+
+        GraduationProgress {
+            plan: self.name.clone(),
+            requirements: progress,
+            surplus,
+        }
+    }
+}
+
+pub fn get_graduation_progress(client: &Client) -> Result<GraduationProgress> {
+    let resp = client::fetch_page(client.get(GRADUATION_PROGRESS_PAGE_URL))?;
+
+    parse_graduation_progress(&Html::parse_document(&resp))
+}
+
+/// Reads the requirement areas off the Graduation Progress page, by their column headers. The
+/// page's total row is left out, since the totals are sums of the areas. HAC doesn't list the
+/// courses counted towards each area.
+pub(crate) fn parse_graduation_progress(document: &Html) -> Result<GraduationProgress> {
+    let header_row = document
+        .select(selector!("tr.sg-asp-table-header-row"))
+        .find(|row| {
+            let headers = html::header_names(*row);
+            headers
+                .iter()
+                .any(|h| h.to_lowercase().contains("required"))
+        })
+        .ok_or(anyhow!("missing graduation progress table"))?;
+
+    let headers = html::header_names(header_row);
+    let column = |names: &[&str]| names.iter().find_map(|name| html::column(&headers, name));
+    let required = |names: &[&str]| column(names).ok_or(anyhow!("missing {} column", names[0]));
+
+    let name = required(&[
+        "Subject Area",
+        "Area",
+        "Subject",
+        "Requirement",
+        "Description",
+    ])?;
+    let credits_required = required(&["Required", "Credits Required"])?;
+    let earned = required(&["Earned", "Credits Earned", "Completed"])?;
+    let in_progress = column(&["In Progress", "Credits In Progress", "Enrolled"]);
+    let remaining = column(&["Remaining", "Needed", "Credits Needed"]);
+
+    let table = header_row
+        .ancestors()
+        .filter_map(scraper::ElementRef::wrap)
+        .find(|e| e.value().name() == "table")
+        .ok_or(anyhow!("missing graduation progress table"))?;
+
+    // HAC leaves credit cells blank rather than writing 0
+    let credits = |cell: Option<&String>| {
+        cell.and_then(|c| c.trim().parse::<f32>().ok())
+            .unwrap_or(0.0)
+    };
+
+    let requirements = table
+        .select(selector!("tr.sg-asp-table-data-row"))
+        .filter_map(|row| {
+            let cells = row.child_elements().map(html::text).collect::<Vec<_>>();
+            let name = cells.get(name)?.clone();
+
+            if name.is_empty() || name.to_lowercase().starts_with("total") {
+                return None;
+            }
+
+            let required = credits(cells.get(credits_required));
+            let earned = credits(cells.get(earned));
+            let in_progress = credits(in_progress.and_then(|i| cells.get(i)));
+            let remaining = match remaining {
+                Some(i) => credits(cells.get(i)),
+                None => (required - earned - in_progress).max(0.0),
+            };
+
+            Some(RequirementProgress {
+                name,
+                required,
+                earned,
+                in_progress,
+                remaining,
+                courses: Vec::new(),
+            })
+        })
+        .collect();
+
+    let plan = document
+        .select(selector!(
+            r#"span[id*="lblProgram"], span[id*="lblGradPlan"]"#
+        ))
+        .next()
+        .map(html::text)
+        .filter(|p| !p.is_empty())
+        .unwrap_or_else(|| "HAC".to_owned());
+
+    Ok(GraduationProgress {
+        plan,
+        requirements,
+        surplus: 0.0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcript::{Grade, TranscriptEntry};
+
+    fn entry(code: &str, name: &str, grade: impl Into<Grade>, credits: f32) -> TranscriptEntry {
+        TranscriptEntry {
+            credits: Some(credits),
+            ..TranscriptEntry::with_id(code, name.to_owned(), grade)
+        }
+    }
+
+    #[test]
+    fn foundation_progress() {
+        let transcript = Transcript {
+            entries: vec![
+                entry("ENG1200A", "English 1 Adv", 95.0, 1.0),
+                entry("MTH1200", "Algebra 1", 65.0, 1.0),
+                entry("HLT1100", "Health", Grade::Pass, 0.5),
+                entry("SPA1100", "Spanish 1", 90.0, 1.0),
+                entry("SPA1200", "Spanish 2", 90.0, 1.0),
+                entry("SPA1300", "Spanish 3", 90.0, 1.0),
+                entry("LDR1100", "Study Skills LC", 100.0, 1.0),
+            ],
+        };
+
+        let schedule = crate::schedule::parse_schedule(&scraper::Html::parse_document(
+            r#"
+            <table id="plnMain_dgSchedule">
+                <tr class="sg-asp-table-header-row">
+                    <td>Course</td><td>Description</td><td>Periods</td><td>Teacher</td>
+                    <td>Room</td><td>Days</td><td>Marking Periods</td>
+                </tr>
+                <tr class="sg-asp-table-data-row">
+                    <td>ENG2200A - 3</td><td>English 2 Adv</td><td>1</td><td>Smith</td>
+                    <td>B204</td><td>A</td><td>Q1, Q2, Q3, Q4</td>
+                </tr>
+                <tr class="sg-asp-table-data-row">
+                    <td>SPC1100 - 1</td><td>Professional Communications</td><td>2</td>
+                    <td>Lee</td><td>C110</td><td>B</td><td>S2</td>
+                </tr>
+            </table>
+            "#,
+        ))
+        .unwrap();
+
+        let plan = GraduationPlan::default();
+        let progress = plan.progress(Catalog::bundled(), &transcript, &schedule);
+        let requirement = |name: &str| {
+            progress
+                .requirements
+                .iter()
+                .find(|r| r.name == name)
+                .unwrap()
+        };
+
+        assert_eq!(progress.required(), 26.0);
+
+        let english = requirement("English");
+        assert_eq!((english.earned, english.in_progress), (1.0, 1.0));
+        assert_eq!(english.remaining, 2.0);
+
+        // failed
+        assert_eq!(requirement("Mathematics").earned, 0.0);
+        assert_eq!(requirement("Health").earned, 0.5);
+        assert_eq!(requirement("Speech").in_progress, 0.5);
+
+        // the third year of Spanish spills over into electives
+        assert_eq!(requirement("Languages Other Than English").earned, 2.0);
+        let electives = requirement("Electives");
+        assert_eq!(electives.earned, 1.0);
+        assert_eq!(electives.courses, ["Spanish 3"]);
+
+        assert_eq!(progress.earned(), 4.5);
+        assert_eq!(progress.in_progress(), 1.5);
+        assert_eq!(progress.remaining(), 20.0);
+    }
+
+    #[test]
+    fn posted_semester() {
+        let transcript = Transcript {
+            entries: vec![entry("ENG2200A", "English 2 Adv", 92.0, 0.5)],
+        };
+
+        let schedule = crate::schedule::parse_schedule(&scraper::Html::parse_document(
+            r#"
+            <table id="plnMain_dgSchedule">
+                <tr class="sg-asp-table-header-row">
+                    <td>Course</td><td>Description</td><td>Periods</td><td>Teacher</td>
+                    <td>Room</td><td>Days</td><td>Marking Periods</td>
+                </tr>
+                <tr class="sg-asp-table-data-row">
+                    <td>ENG2200A - 3</td><td>English 2 Adv</td><td>1</td><td>Smith</td>
+                    <td>B204</td><td>A</td><td>Q1, Q2, Q3, Q4</td>
+                </tr>
+                <tr class="sg-asp-table-data-row">
+                    <td>CHM1100A - 1</td><td>Chemistry Adv</td><td>2</td><td>Lee</td>
+                    <td>C110</td><td>B</td><td>Q1, Q2, Q3, Q4</td>
+                </tr>
+            </table>
+            "#,
+        ))
+        .unwrap();
+
+        let progress =
+            GraduationPlan::default().progress(Catalog::bundled(), &transcript, &schedule);
+
+        // the first semester of English is posted, so only the second is in progress
+        let english = &progress.requirements[0];
+        assert_eq!((english.earned, english.in_progress), (0.5, 0.5));
+        assert_eq!(english.remaining, 3.0);
+
+        assert_eq!(progress.earned(), 0.5);
+        assert_eq!(progress.in_progress(), 1.5);
+    }
+
+    #[test]
+    fn graduation_progress_page() {
+        let document = scraper::Html::parse_document(
+            r#"
+            <span id="plnMain_lblProgram">Foundation with Endorsement</span>
+            <table id="plnMain_dgCredits">
+                <tr class="sg-asp-table-header-row">
+                    <td>Subject Area</td><td>Required</td><td>Earned</td><td>In Progress</td>
+                    <td>Remaining</td>
+                </tr>
+                <tr class="sg-asp-table-data-row">
+                    <td>English</td><td>4.00</td><td>1.00</td><td>1.00</td><td>2.00</td>
+                </tr>
+                <tr class="sg-asp-table-data-row">
+                    <td>Fine Arts</td><td>1.00</td><td></td><td>0.50</td><td>0.50</td>
+                </tr>
+                <tr class="sg-asp-table-data-row">
+                    <td>Total</td><td>5.00</td><td>1.00</td><td>1.50</td><td>2.50</td>
+                </tr>
+            </table>
+            "#,
+        );
+
+        let progress = parse_graduation_progress(&document).unwrap();
+
+        assert_eq!(progress.plan, "Foundation with Endorsement");
+        assert_eq!(progress.requirements.len(), 2);
+        assert_eq!(progress.requirements[1].name, "Fine Arts");
+        assert_eq!(progress.requirements[1].earned, 0.0);
+        assert_eq!(progress.required(), 5.0);
+        assert_eq!(progress.remaining(), 2.5);
+    }
+}
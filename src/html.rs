@@ -0,0 +1,29 @@
+use chrono::NaiveDate;
+use scraper::ElementRef;
+
+use crate::selector;
+
+/// Returns all of the text inside an element, with runs of whitespace collapsed into a single
+/// space.
+pub(crate) fn text(element: ElementRef) -> String {
+    element
+        .text()
+        .flat_map(str::split_whitespace)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Returns the trimmed text of every cell in a table header row, in order.
+pub(crate) fn header_names(row: ElementRef) -> Vec<String> {
+    row.select(selector!("th, td")).map(text).collect()
+}
+
+/// Finds the index of the column whose header matches `name`, ignoring case.
+pub(crate) fn column(headers: &[String], name: &str) -> Option<usize> {
+    headers.iter().position(|h| h.eq_ignore_ascii_case(name))
+}
+
+/// Parses a date in the `MM/DD/YYYY` format HAC uses everywhere.
+pub(crate) fn parse_date(text: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(text.trim(), "%m/%d/%Y").ok()
+}
@@ -0,0 +1,446 @@
+//! Exporting to iCalendar (RFC 5545), for importing into phone and desktop calendars.
+//!
+//! Times are written as floating local times, so events show up at the right time of day in
+//! whatever time zone the calendar app is set to.
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Utc, Weekday};
+
+use crate::{
+    assignments::{AssignmentStatus, ClassAssignments},
+    calendar::{Calendar, Meeting},
+    schedule::{Course, Day},
+};
+
+const PRODUCT_ID: &str = "-//hac//HAC export//EN";
+
+/// Lines longer than this many bytes are folded, as RFC 5545 requires.
+const MAX_LINE_LENGTH: usize = 75;
+
+/// A single `VEVENT`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Event {
+    pub uid: String,
+    pub summary: String,
+    pub location: Option<String>,
+    pub description: Option<String>,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    /// Whether `start` and `end` are whole days rather than times.
+    pub all_day: bool,
+    pub recurrence: Option<Recurrence>,
+    /// How long before the start to show reminders.
+    pub alarms: Vec<TimeDelta>,
+}
+
+/// A weekly repeat on some weekdays, with the dates it skips.
+#[derive(Debug, Clone)]
+pub(crate) struct Recurrence {
+    pub weekdays: Vec<Weekday>,
+    /// The start of the last occurrence.
+    pub until: NaiveDateTime,
+    /// The starts of skipped occurrences.
+    pub except: Vec<NaiveDateTime>,
+}
+
+/// Escapes text for use in a property value.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn date_time(time: NaiveDateTime) -> String {
+    time.format("%Y%m%dT%H%M%S").to_string()
+}
+
+fn date(date: NaiveDate) -> String {
+    date.format("%Y%m%d").to_string()
+}
+
+fn weekday(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+/// Writes a content line, folding it onto continuation lines if it's too long.
+fn line(out: &mut String, line: &str) {
+    let mut start = 0;
+
+    while line.len() - start > MAX_LINE_LENGTH {
+        // continuation lines start with a space, which counts towards their length
+        let mut end = start + MAX_LINE_LENGTH - usize::from(start > 0);
+
+        while !line.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        if start > 0 {
+            out.push(' ');
+        }
+        out.push_str(&line[start..end]);
+        out.push_str("\r\n");
+
+        start = end;
+    }
+
+    if start > 0 {
+        out.push(' ');
+    }
+    out.push_str(&line[start..]);
+    out.push_str("\r\n");
+}
+
+impl Event {
+    fn write(&self, out: &mut String, stamp: DateTime<Utc>) {
+        line(out, "BEGIN:VEVENT");
+        line(out, &format!("UID:{}", self.uid));
+        line(out, &format!("DTSTAMP:{}Z", date_time(stamp.naive_utc())));
+
+        if self.all_day {
+            line(
+                out,
+                &format!("DTSTART;VALUE=DATE:{}", date(self.start.date())),
+            );
+            line(out, &format!("DTEND;VALUE=DATE:{}", date(self.end.date())));
+        } else {
+            line(out, &format!("DTSTART:{}", date_time(self.start)));
+            line(out, &format!("DTEND:{}", date_time(self.end)));
+        }
+
+        line(out, &format!("SUMMARY:{}", escape(&self.summary)));
+
+        if let Some(location) = &self.location {
+            line(out, &format!("LOCATION:{}", escape(location)));
+        }
+
+        if let Some(description) = &self.description {
+            line(out, &format!("DESCRIPTION:{}", escape(description)));
+        }
+
+        if let Some(recurrence) = &self.recurrence {
+            let days = recurrence
+                .weekdays
+                .iter()
+                .map(|d| weekday(*d))
+                .collect::<Vec<_>>()
+                .join(",");
+
+            line(
+                out,
+                &format!(
+                    "RRULE:FREQ=WEEKLY;BYDAY={days};UNTIL={}",
+                    date_time(recurrence.until)
+                ),
+            );
+
+            for except in &recurrence.except {
+                line(out, &format!("EXDATE:{}", date_time(*except)));
+            }
+        }
+
+        for alarm in &self.alarms {
+            line(out, "BEGIN:VALARM");
+            line(out, "ACTION:DISPLAY");
+            line(out, &format!("DESCRIPTION:{}", escape(&self.summary)));
+            line(out, &format!("TRIGGER:-PT{}M", alarm.num_minutes()));
+            line(out, "END:VALARM");
+        }
+
+        line(out, "END:VEVENT");
+    }
+}
+
+/// Wraps events in a `VCALENDAR`.
+pub(crate) fn write_calendar(name: &str, events: &[Event], stamp: DateTime<Utc>) -> String {
+    let mut out = String::new();
+
+    line(&mut out, "BEGIN:VCALENDAR");
+    line(&mut out, "VERSION:2.0");
+    line(&mut out, &format!("PRODID:{PRODUCT_ID}"));
+    line(&mut out, "CALSCALE:GREGORIAN");
+    line(&mut out, &format!("X-WR-CALNAME:{}", escape(name)));
+
+    for event in events {
+        event.write(&mut out, stamp);
+    }
+
+    line(&mut out, "END:VCALENDAR");
+
+    out
+}
+
+/// Exports the schedule for the whole school year as recurring events, one series per course
+/// period and bell time. Each series repeats weekly on the days the course can meet and skips
+/// holidays, the other rotation day and days on a different bell schedule (which get their own
+/// series). `stamp` is recorded as when the events were created.
+pub fn schedule(calendar: &Calendar, courses: &[Course], stamp: DateTime<Utc>) -> String {
+    // every meeting of the year, grouped by course period and bell times
+    let mut series: Vec<(Meeting, Vec<NaiveDate>)> = Vec::new();
+
+    for date in calendar
+        .first_day
+        .iter_days()
+        .take_while(|d| *d <= calendar.last_day)
+    {
+        for meeting in calendar.meetings(courses, date) {
+            let same = |m: &Meeting| {
+                m.course.id() == meeting.course.id()
+                    && m.period == meeting.period
+                    && m.start.time() == meeting.start.time()
+                    && m.end.time() == meeting.end.time()
+            };
+
+            match series.iter_mut().find(|(m, _)| same(m)) {
+                Some((_, dates)) => dates.push(date),
+                None => series.push((meeting, vec![date])),
+            }
+        }
+    }
+
+    let events = series
+        .into_iter()
+        .map(|(meeting, dates)| {
+            let (course, period) = (meeting.course, meeting.period);
+            let (start, end) = (meeting.start.time(), meeting.end.time());
+            let first = dates[0];
+            let last = dates[dates.len() - 1];
+
+            let weekdays = match period.day() {
+                Day::Weekdays(days) => days.clone(),
+                _ => vec![
+                    Weekday::Mon,
+                    Weekday::Tue,
+                    Weekday::Wed,
+                    Weekday::Thu,
+                    Weekday::Fri,
+                ],
+            };
+
+            let except = first
+                .iter_days()
+                .take_while(|d| *d <= last)
+                .filter(|d| weekdays.contains(&d.weekday()) && !dates.contains(d))
+                .map(|d| d.and_time(start))
+                .collect();
+
+            let recurrence = (dates.len() > 1).then(|| Recurrence {
+                weekdays,
+                until: last.and_time(start),
+                except,
+            });
+
+            Event {
+                uid: format!(
+                    "{}-{}-{}@hac",
+                    course.id().replace(' ', ""),
+                    period.to_string().replace(' ', ""),
+                    start.format("%H%M")
+                ),
+                summary: course.name().to_owned(),
+                location: Some(course.classroom_in(period).to_owned()).filter(|c| !c.is_empty()),
+                description: match &course.teacher_in(period).email {
+                    Some(email) => Some(format!("{} <{email}>", course.teacher_in(period))),
+                    None => Some(course.teacher_in(period).to_string()).filter(|t| !t.is_empty()),
+                },
+                start: first.and_time(start),
+                end: first.and_time(end),
+                recurrence,
+                ..Default::default()
+            }
+        })
+        .collect::<Vec<_>>();
+
+    write_calendar("Class schedule", &events, stamp)
+}
+
+/// Keeps only letters and digits, for building UIDs.
+fn slug(text: &str) -> String {
+    text.chars().filter(char::is_ascii_alphanumeric).collect()
+}
+
+/// Exports a feed of assignments as all day events on their due dates: everything due on or after
+/// `today`, and everything flagged missing or already past due without a score, which is marked
+/// missing. Exempt assignments are left out. Each event
+/// gets a reminder `reminders` before the start of its due date.
+pub fn assignments(
+    classes: &[ClassAssignments],
+    today: NaiveDate,
+    reminders: &[TimeDelta],
+    stamp: DateTime<Utc>,
+) -> String {
+    let events = classes
+        .iter()
+        .flat_map(|class| class.assignments.iter().map(move |a| (class, a)))
+        .filter_map(|(class, assignment)| {
+            let due = assignment.date_due?;
+            let missing = match assignment.status {
+                AssignmentStatus::Missing => true,
+                AssignmentStatus::Exempt => return None,
+                _ => due < today && assignment.score.is_none(),
+            };
+
+            if due < today && !missing {
+                return None;
+            }
+
+            let summary = match missing {
+                true => format!("Missing: {} ({})", assignment.name, class.course),
+                false => format!("{} ({})", assignment.name, class.course),
+            };
+
+            let mut description = assignment.category.clone();
+            if let Some(total) = assignment.total_points {
+                description.push_str(&format!(", {total} points"));
+            }
+
+            Some(Event {
+                uid: format!(
+                    "{}-{}-{}@hac",
+                    slug(&class.course),
+                    slug(&assignment.name),
+                    date(due)
+                ),
+                summary,
+                description: Some(description),
+                start: due.and_time(NaiveTime::MIN),
+                end: (due + TimeDelta::days(1)).and_time(NaiveTime::MIN),
+                all_day: true,
+                // reminders for missing work would only go off in the past
+                alarms: if missing {
+                    Vec::new()
+                } else {
+                    reminders.to_vec()
+                },
+                ..Default::default()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    write_calendar("Assignments", &events, stamp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assignments::Assignment;
+
+    const CALENDAR: &str = r#"
+        first_day = "2024-08-14"
+        last_day = "2024-08-23"
+
+        [[holidays]]
+        start = "2024-08-20"
+
+        [[schedules]]
+        name = "regular"
+        bells = [{ period = "1", start = "08:45", end = "10:15" }]
+
+        [[schedules]]
+        name = "early release"
+        bells = [{ period = "1", start = "08:45", end = "09:30" }]
+
+        [[special_days]]
+        date = "2024-08-22"
+        schedule = "early release"
+    "#;
+
+    const SCHEDULE: &str = r#"
+        <table id="plnMain_dgSchedule">
+            <tr class="sg-asp-table-header-row">
+                <td>Course</td><td>Description</td><td>Periods</td><td>Teacher</td>
+                <td>Room</td><td>Days</td>
+            </tr>
+            <tr class="sg-asp-table-data-row">
+                <td>ENG2200A - 3</td><td>English 2 Adv</td><td>1</td><td>Smith, Jane</td>
+                <td>B204</td><td>A</td>
+            </tr>
+        </table>
+    "#;
+
+    #[test]
+    fn schedule_events() {
+        let calendar: Calendar = toml::from_str(CALENDAR).unwrap();
+        let courses =
+            crate::schedule::parse_schedule(&scraper::Html::parse_document(SCHEDULE)).unwrap();
+        let stamp = DateTime::parse_from_rfc3339("2024-08-01T00:00:00Z")
+            .unwrap()
+            .to_utc();
+
+        let ics = schedule(&calendar, &courses, stamp);
+
+        // A days: 14th, 16th, 21st (after the holiday on the 20th) and the 23rd, with the early
+        // release on the 22nd being a B day
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.contains(
+            "BEGIN:VEVENT\r\nUID:ENG2200A-3-1A-0845@hac\r\nDTSTAMP:20240801T000000Z\r\n\
+             DTSTART:20240814T084500\r\nDTEND:20240814T101500\r\nSUMMARY:English 2 Adv\r\n\
+             LOCATION:B204\r\nDESCRIPTION:Smith\\, Jane\r\n\
+             RRULE:FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR;UNTIL=20240823T084500\r\n\
+             EXDATE:20240815T084500\r\nEXDATE:20240819T084500\r\nEXDATE:20240820T084500\r\n\
+             EXDATE:20240822T084500\r\nEND:VEVENT\r\n"
+        ));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+    }
+
+    #[test]
+    fn folds_long_lines() {
+        let mut out = String::new();
+        line(&mut out, &"x".repeat(160));
+
+        let lines = out.split_terminator("\r\n").collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert!(lines.iter().all(|l| l.len() <= MAX_LINE_LENGTH));
+        assert_eq!(out.replace("\r\n ", "").trim_end(), "x".repeat(160));
+    }
+
+    #[test]
+    fn assignment_events() {
+        let assignment = |name: &str, day, score| Assignment {
+            name: name.to_owned(),
+            category: "Major".to_owned(),
+            date_due: NaiveDate::from_ymd_opt(2024, 9, day),
+            date_assigned: None,
+            score,
+            total_points: Some(100.0),
+            status: AssignmentStatus::Normal,
+        };
+        let classes = [ClassAssignments {
+            course: "Chemistry Adv".to_owned(),
+            average: Some(90.0),
+            assignments: vec![
+                assignment("Lab 1", 3, Some(90.0)),
+                assignment("Lab 2", 4, None),
+                assignment("Lab 3; Gases", 12, None),
+            ],
+        }];
+        let stamp = DateTime::parse_from_rfc3339("2024-09-10T00:00:00Z")
+            .unwrap()
+            .to_utc();
+
+        let ics = assignments(
+            &classes,
+            NaiveDate::from_ymd_opt(2024, 9, 10).unwrap(),
+            &[TimeDelta::hours(15)],
+            stamp,
+        );
+
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+        assert!(!ics.contains("Lab 1"));
+        assert!(ics.contains("SUMMARY:Missing: Lab 2 (Chemistry Adv)\r\n"));
+        assert!(ics.contains(
+            "UID:ChemistryAdv-Lab3Gases-20240912@hac\r\nDTSTAMP:20240910T000000Z\r\n\
+             DTSTART;VALUE=DATE:20240912\r\nDTEND;VALUE=DATE:20240913\r\n\
+             SUMMARY:Lab 3\\; Gases (Chemistry Adv)\r\nDESCRIPTION:Major\\, 100 points\r\n\
+             BEGIN:VALARM\r\nACTION:DISPLAY\r\n"
+        ));
+        assert_eq!(ics.matches("TRIGGER:-PT900M").count(), 1);
+    }
+}
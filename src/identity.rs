@@ -0,0 +1,198 @@
+//! Matching the same course across HAC's pages, which name courses differently: the transcript
+//! might say "AP Computer Science A" where the assignments page says "AP Comp Sci A S1".
+
+use crate::catalog::CourseId;
+
+/// Abbreviations HAC uses in course names, and what they stand for.
+const ABBREVIATIONS: &[(&str, &str)] = &[
+    ("adv", "advanced"),
+    ("alg", "algebra"),
+    ("bio", "biology"),
+    ("calc", "calculus"),
+    ("chem", "chemistry"),
+    ("comp", "computer"),
+    ("econ", "economics"),
+    ("eng", "english"),
+    ("geom", "geometry"),
+    ("gov", "government"),
+    ("govt", "government"),
+    ("hist", "history"),
+    ("lang", "language"),
+    ("lit", "literature"),
+    ("math", "mathematics"),
+    ("phys", "physics"),
+    ("prin", "principles"),
+    ("psych", "psychology"),
+    ("sci", "science"),
+    ("stat", "statistics"),
+    ("stats", "statistics"),
+    ("i", "1"),
+    ("ii", "2"),
+    ("iii", "3"),
+    ("iv", "4"),
+];
+
+/// Words that change a course's level, so names that differ in them are different courses.
+const LEVEL_WORDS: &[&str] = &["advanced", "ap", "ib", "dc", "gt", "honors"];
+
+/// Words marking a semester or part of the year, which don't change the course.
+const TERM_WORDS: &[&str] = &["s1", "s2", "sem", "semester", "q1", "q2", "q3", "q4"];
+
+/// A course as one page identifies it: by its code, if the page shows one, and its name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CourseIdentity {
+    /// The course code without the section or semester (eg. "ENG2200A").
+    pub code: Option<String>,
+    pub name: String,
+    /// The name, lowercased, split into words and with abbreviations expanded.
+    tokens: Vec<String>,
+}
+
+impl CourseIdentity {
+    /// `code` may be a bare code or a full HAC id (eg. "ENG2200A - 3").
+    pub fn new(code: Option<&str>, name: &str) -> Self {
+        Self {
+            code: code.and_then(CourseId::parse).map(|id| id.code),
+            name: name.to_owned(),
+            tokens: tokens(name),
+        }
+    }
+
+    /// Whether both identify the same course. Codes decide when both are known; otherwise the
+    /// names must agree on numbers and level words, and every word of the shorter name must be a
+    /// word of the longer one. Words are only matched whole (after expanding the abbreviations
+    /// HAC uses), so "Math" doesn't match "Mathematical Models".
+    pub fn matches(&self, other: &CourseIdentity) -> bool {
+        if let (Some(a), Some(b)) = (&self.code, &other.code) {
+            return a == b;
+        }
+
+        if self.tokens == other.tokens {
+            return true;
+        }
+
+        let significant = |tokens: &[String]| {
+            let mut words = tokens
+                .iter()
+                .filter(|t| {
+                    LEVEL_WORDS.contains(&t.as_str()) || t.chars().all(|c| c.is_ascii_digit())
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+            words.sort();
+            words
+        };
+
+        if significant(&self.tokens) != significant(&other.tokens) {
+            return false;
+        }
+
+        let (shorter, longer) = if self.tokens.len() <= other.tokens.len() {
+            (&self.tokens, &other.tokens)
+        } else {
+            (&other.tokens, &self.tokens)
+        };
+
+        !shorter.is_empty() && shorter.iter().all(|a| longer.contains(a))
+    }
+}
+
+fn tokens(name: &str) -> Vec<String> {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .filter(|word| !TERM_WORDS.contains(&word.as_str()))
+        .map(|word| {
+            ABBREVIATIONS
+                .iter()
+                .find(|(short, _)| *short == word)
+                .map_or(word, |(_, long)| (*long).to_owned())
+        })
+        .collect()
+}
+
+/// Assigns the same index to every identity of a course, in the order courses are first seen.
+#[derive(Debug, Clone, Default)]
+pub struct CourseResolver {
+    /// Every identity seen for each course.
+    courses: Vec<Vec<CourseIdentity>>,
+}
+
+impl CourseResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The index of the course `code` and `name` identify, adding a new course if none of the
+    /// ones seen so far match.
+    pub fn resolve(&mut self, code: Option<&str>, name: &str) -> usize {
+        let identity = CourseIdentity::new(code, name);
+
+        let index = self
+            .courses
+            .iter()
+            .position(|seen| seen.iter().any(|s| s.matches(&identity)));
+
+        match index {
+            Some(index) => {
+                if !self.courses[index].contains(&identity) {
+                    self.courses[index].push(identity);
+                }
+                index
+            }
+            None => {
+                self.courses.push(vec![identity]);
+                self.courses.len() - 1
+            }
+        }
+    }
+
+    /// The number of distinct courses seen.
+    pub fn len(&self) -> usize {
+        self.courses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.courses.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matching() {
+        let same = |a: (Option<&str>, &str), b: (Option<&str>, &str)| {
+            CourseIdentity::new(a.0, a.1).matches(&CourseIdentity::new(b.0, b.1))
+        };
+
+        assert!(same(
+            (None, "AP Computer Science A"),
+            (None, "AP Comp Sci A S1")
+        ));
+        assert!(same((None, "English II Adv"), (None, "English 2 Advanced")));
+        assert!(same(
+            (Some("ENG2200A"), "English 2"),
+            (Some("ENG2200A - 3"), "Eng II Adv")
+        ));
+
+        assert!(!same((None, "English 2 Adv"), (None, "English 3 Adv")));
+        assert!(!same((None, "Algebra 2"), (None, "Algebra 2 Adv")));
+        assert!(!same((None, "AP Physics 1"), (None, "AP Psychology")));
+        assert!(same((None, "AP Stat"), (None, "AP Statistics")));
+        assert!(!same((None, "Math"), (None, "Mathematical Models")));
+        // codes win over names
+        assert!(!same(
+            (Some("MTH4100"), "Calculus"),
+            (Some("MTH4200"), "Calculus")
+        ));
+
+        let mut resolver = CourseResolver::new();
+        assert_eq!(resolver.resolve(None, "AP Computer Science A"), 0);
+        assert_eq!(resolver.resolve(Some("ENG2200A - 3"), "English 2 Adv"), 1);
+        assert_eq!(resolver.resolve(None, "AP Comp Sci A S2"), 0);
+        assert_eq!(resolver.resolve(Some("ENG2200A"), "Eng II Adv"), 1);
+        assert_eq!(resolver.len(), 2);
+    }
+}
@@ -1,11 +1,55 @@
 #![allow(dead_code)]
 
+mod adjustments;
+mod assignments;
+mod attendance;
+mod catalog;
+mod changes;
 mod classes;
+mod graduation;
+mod html;
+mod identity;
+mod missing;
+mod policy;
+mod rank;
 mod schedule;
+mod snapshot;
+mod teachers;
 mod transcript;
+mod trends;
 
+#[cfg(feature = "serde")]
+pub mod calendar;
 pub mod client;
-pub use transcript::{MergeStrategy, Transcript};
+pub mod credentials;
+#[cfg(feature = "serde")]
+pub mod ics;
+#[cfg(feature = "serde")]
+pub mod notify;
+#[cfg(feature = "serde")]
+pub mod poll;
+#[cfg(feature = "sqlite")]
+pub mod store;
+
+pub use adjustments::{Action, AdjustmentRule, Adjustments, AppliedAdjustment, GradeSource};
+pub use assignments::{Assignment, AssignmentStatus, ClassAssignments};
+pub use attendance::{AttendanceDay, AttendanceEvent};
+pub use catalog::{
+    Catalog, CatalogCourse, Classification, CourseId, Level, LevelRule, SubjectRule,
+};
+pub use changes::{diff, AverageChange, GradeChanges, PostedAssignment, ScoreChange};
+pub use graduation::{GraduationPlan, GraduationProgress, Requirement, RequirementProgress};
+pub use identity::{CourseIdentity, CourseResolver};
+pub use missing::{missing_assignments, MissingAssignment};
+pub use policy::{ExcludedCourse, ExclusionReason, GpaPolicy, PolicyRule};
+pub use rank::{project_rank, RankProjection, RankThreshold, DEFAULT_GPA_SPREAD};
+pub use schedule::{Course, CourseStatus, Day, Period, PeriodNumber};
+pub use snapshot::Snapshot;
+pub use teachers::{teacher_directory, Teacher, TeacherEntry};
+pub use transcript::{
+    GpaScale, Grade, MergeStrategy, Transcript, TranscriptEntry, TranscriptSummary,
+};
+pub use trends::{estimate_run_end, trends, AssignmentImpact, AveragePoint, ClassTrend};
 
 #[macro_export]
 macro_rules! selector {
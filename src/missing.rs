@@ -0,0 +1,110 @@
+use crate::assignments::{Assignment, AssignmentStatus, ClassAssignments};
+
+/// An assignment flagged as missing, late or zero, and what it is costing the class average.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MissingAssignment {
+    pub course: String,
+    pub assignment: Assignment,
+    /// Roughly how many points the class average would rise if the assignment earned full
+    /// credit, or `None` if it isn't in the average yet. This is based on points alone, since HAC
+    /// doesn't say how categories are weighted.
+    pub impact: Option<f32>,
+}
+
+/// Whether an assignment counts towards the average: it has a score or is being counted as a
+/// zero, and isn't exempt.
+fn counts(assignment: &Assignment) -> bool {
+    assignment.status != AssignmentStatus::Exempt
+        && (assignment.score.is_some()
+            || matches!(
+                assignment.status,
+                AssignmentStatus::Missing | AssignmentStatus::Zero
+            ))
+}
+
+/// Finds every missing, zero and late assignment across the classes, biggest impact first.
+pub fn missing_assignments(classes: &[ClassAssignments]) -> Vec<MissingAssignment> {
+    let mut missing = classes
+        .iter()
+        .flat_map(|class| {
+            let counted_points = class
+                .assignments
+                .iter()
+                .filter(|a| counts(a))
+                .filter_map(|a| a.total_points)
+                .sum::<f32>();
+
+            class
+                .assignments
+                .iter()
+                .filter(|a| a.status.needs_attention())
+                .map(move |assignment| {
+                    // an assignment that isn't in the average yet (eg. late and ungraded) isn't
+                    // costing it anything
+                    let impact = assignment
+                        .total_points
+                        .filter(|_| counts(assignment) && counted_points > 0.0)
+                        .map(|total| {
+                            (total - assignment.score.unwrap_or(0.0)) / counted_points * 100.0
+                        });
+
+                    MissingAssignment {
+                        course: class.course.clone(),
+                        assignment: assignment.clone(),
+                        impact,
+                    }
+                })
+        })
+        .collect::<Vec<_>>();
+
+    missing.sort_by(|a, b| {
+        let impact = |m: &MissingAssignment| m.impact.unwrap_or(f32::NEG_INFINITY);
+        impact(b).total_cmp(&impact(a))
+    });
+
+    missing
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assignment(name: &str, score: Option<f32>, status: AssignmentStatus) -> Assignment {
+        Assignment {
+            name: name.to_owned(),
+            category: "Minor Grades".to_owned(),
+            date_due: None,
+            date_assigned: None,
+            score,
+            total_points: Some(50.0),
+            status,
+        }
+    }
+
+    #[test]
+    fn sorted_by_impact() {
+        let classes = [ClassAssignments {
+            course: "English 2 Adv".to_owned(),
+            average: Some(70.0),
+            assignments: vec![
+                assignment("Vocab 1", Some(50.0), AssignmentStatus::Normal),
+                assignment("Vocab 2", Some(40.0), AssignmentStatus::Late),
+                assignment("Vocab 3", None, AssignmentStatus::Missing),
+                assignment("Vocab 4", None, AssignmentStatus::Exempt),
+                assignment("Vocab 5", None, AssignmentStatus::Normal),
+                assignment("Vocab 6", None, AssignmentStatus::Late),
+            ],
+        }];
+
+        let missing = missing_assignments(&classes);
+
+        assert_eq!(missing.len(), 3);
+        assert_eq!(missing[0].assignment.name, "Vocab 3");
+        assert_eq!(missing[0].impact, Some(50.0 / 150.0 * 100.0));
+        assert_eq!(missing[1].assignment.name, "Vocab 2");
+        assert_eq!(missing[1].impact, Some(10.0 / 150.0 * 100.0));
+        assert_eq!(missing[2].assignment.name, "Vocab 6");
+        assert_eq!(missing[2].impact, None);
+    }
+}
@@ -0,0 +1,513 @@
+//! Sending grade changes somewhere a student will see them.
+//!
+//! Notifiers are usually built from a TOML config file (see [`NotifierConfig`]):
+//!
+//! ```toml
+//! [[webhook]]
+//! url = "https://discord.com/api/webhooks/..."
+//!
+//! [[ntfy]]
+//! url = "https://ntfy.sh/my-grades"
+//!
+//! # requires the `email` feature
+//! [[email]]
+//! host = "smtp.example.com"
+//! port = 587
+//! username = "me@example.com"
+//! password = "app-password"
+//! from = "me@example.com"
+//! to = "me@example.com"
+//! ```
+
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use reqwest::blocking::Client;
+use serde::Deserialize;
+
+use crate::changes::GradeChanges;
+
+/// Discord rejects messages longer than this.
+const MAX_MESSAGE_LENGTH: usize = 2000;
+
+/// Something that can deliver a summary of grade changes.
+pub trait Notifier {
+    /// A short description of the notifier, used in error messages.
+    fn name(&self) -> String;
+
+    fn notify(&self, student: &str, changes: &GradeChanges) -> Result<()>;
+}
+
+fn title(student: &str) -> String {
+    format!("New grades for {student}")
+}
+
+/// The plain text message sent by notifiers, truncated to fit in chat messages.
+fn message(student: &str, changes: &GradeChanges) -> String {
+    let mut message = format!("{}:\n{changes}", title(student));
+
+    if message.len() > MAX_MESSAGE_LENGTH {
+        let mut end = MAX_MESSAGE_LENGTH - 3;
+
+        while !message.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        message.truncate(end);
+        message.push_str("...");
+    }
+
+    message
+}
+
+/// Prints changes to stdout.
+pub struct StdoutNotifier;
+
+impl Notifier for StdoutNotifier {
+    fn name(&self) -> String {
+        "stdout".to_owned()
+    }
+
+    fn notify(&self, student: &str, changes: &GradeChanges) -> Result<()> {
+        print!("{}:\n{changes}", title(student));
+
+        Ok(())
+    }
+}
+
+/// POSTs a JSON payload to a URL. The payload carries the message in both `content` (Discord)
+/// and `text` (Slack), alongside the structured `student` and `changes`:
+///
+/// ```json
+/// { "content": "...", "text": "...", "student": "123456", "changes": { ... } }
+/// ```
+pub struct WebhookNotifier {
+    url: String,
+    client: Client,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            client: Client::new(),
+        }
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    fn name(&self) -> String {
+        format!("webhook {}", self.url)
+    }
+
+    fn notify(&self, student: &str, changes: &GradeChanges) -> Result<()> {
+        let message = message(student, changes);
+
+        let payload = serde_json::json!({
+            "content": message,
+            "text": message,
+            "student": student,
+            "changes": changes,
+        });
+
+        self.client
+            .post(&self.url)
+            .json(&payload)
+            .send()?
+            .error_for_status()?;
+
+        Ok(())
+    }
+}
+
+/// Publishes the message as a plain text push notification to an ntfy style topic URL.
+pub struct NtfyNotifier {
+    url: String,
+    client: Client,
+}
+
+impl NtfyNotifier {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            client: Client::new(),
+        }
+    }
+}
+
+impl Notifier for NtfyNotifier {
+    fn name(&self) -> String {
+        format!("ntfy {}", self.url)
+    }
+
+    fn notify(&self, student: &str, changes: &GradeChanges) -> Result<()> {
+        self.client
+            .post(&self.url)
+            .header("Title", title(student))
+            .body(changes.to_string())
+            .send()?
+            .error_for_status()?;
+
+        Ok(())
+    }
+}
+
+/// Sends an email through an SMTP server, using STARTTLS unless `starttls` is turned off.
+#[cfg(feature = "email")]
+pub struct SmtpNotifier {
+    config: EmailConfig,
+}
+
+#[cfg(feature = "email")]
+impl SmtpNotifier {
+    pub fn new(config: EmailConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[cfg(feature = "email")]
+impl Notifier for SmtpNotifier {
+    fn name(&self) -> String {
+        format!("email to {}", self.config.to)
+    }
+
+    fn notify(&self, student: &str, changes: &GradeChanges) -> Result<()> {
+        use lettre::{transport::smtp::authentication::Credentials, SmtpTransport, Transport};
+
+        let config = &self.config;
+
+        let email = lettre::Message::builder()
+            .from(config.from.parse()?)
+            .to(config.to.parse()?)
+            .subject(title(student))
+            .body(changes.to_string())?;
+
+        let mut transport = if config.starttls {
+            SmtpTransport::starttls_relay(&config.host)?
+        } else {
+            SmtpTransport::builder_dangerous(&config.host)
+        }
+        .port(config.port);
+
+        if let (Some(username), Some(password)) = (&config.username, &config.password) {
+            transport = transport.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        transport.build().send(&email)?;
+
+        Ok(())
+    }
+}
+
+/// The notifiers to build, as read from a config file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NotifierConfig {
+    #[serde(default)]
+    pub webhook: Vec<UrlConfig>,
+    #[serde(default)]
+    pub ntfy: Vec<UrlConfig>,
+    #[serde(default)]
+    pub email: Vec<EmailConfig>,
+    /// Also print changes to stdout.
+    #[serde(default)]
+    pub stdout: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UrlConfig {
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmailConfig {
+    pub host: String,
+    #[serde(default = "EmailConfig::default_port")]
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    pub to: String,
+    #[serde(default = "EmailConfig::default_starttls")]
+    pub starttls: bool,
+}
+
+impl EmailConfig {
+    fn default_port() -> u16 {
+        587
+    }
+
+    fn default_starttls() -> bool {
+        true
+    }
+}
+
+impl NotifierConfig {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("could not read {}", path.display()))?;
+
+        toml::from_str(&contents).with_context(|| format!("invalid {}", path.display()))
+    }
+
+    /// The default config location (eg. `~/.config/hac/notify.toml`).
+    pub fn default_path() -> Option<std::path::PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("hac").join("notify.toml"))
+    }
+
+    pub fn build(&self) -> Result<Vec<Box<dyn Notifier>>> {
+        let mut notifiers: Vec<Box<dyn Notifier>> = Vec::new();
+
+        if self.stdout {
+            notifiers.push(Box::new(StdoutNotifier));
+        }
+
+        for webhook in &self.webhook {
+            notifiers.push(Box::new(WebhookNotifier::new(&webhook.url)));
+        }
+
+        for ntfy in &self.ntfy {
+            notifiers.push(Box::new(NtfyNotifier::new(&ntfy.url)));
+        }
+
+        #[cfg(feature = "email")]
+        for email in &self.email {
+            notifiers.push(Box::new(SmtpNotifier::new(email.clone())));
+        }
+
+        #[cfg(not(feature = "email"))]
+        if !self.email.is_empty() {
+            bail!("email notifications require the `email` feature");
+        }
+
+        Ok(notifiers)
+    }
+}
+
+/// Sends the changes through every notifier, returning an error listing every notifier that
+/// failed. One failing notifier does not stop the others.
+pub fn notify_all(
+    notifiers: &[Box<dyn Notifier>],
+    student: &str,
+    changes: &GradeChanges,
+) -> Result<()> {
+    let failures = notifiers
+        .iter()
+        .filter_map(|n| {
+            n.notify(student, changes)
+                .err()
+                .map(|e| format!("{}: {e:#}", n.name()))
+        })
+        .collect::<Vec<_>>();
+
+    if !failures.is_empty() {
+        bail!("some notifications failed: {}", failures.join("; "));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread::{self, JoinHandle},
+    };
+
+    use super::*;
+    use crate::changes::AverageChange;
+
+    fn changes() -> GradeChanges {
+        GradeChanges {
+            averages_changed: vec![AverageChange {
+                course: "English 2 Adv".to_owned(),
+                old: Some(90.0),
+                new: Some(92.0),
+            }],
+            ..Default::default()
+        }
+    }
+
+    /// Accepts a single HTTP request on a local port, replies with `204 No Content` and returns
+    /// the request's head and body.
+    fn http_stand_in() -> (String, JoinHandle<(String, String)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+
+            let mut head = String::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                head.push_str(&line);
+            }
+
+            let length = head
+                .lines()
+                .find_map(|l| {
+                    l.to_lowercase()
+                        .strip_prefix("content-length: ")?
+                        .parse()
+                        .ok()
+                })
+                .unwrap_or(0);
+
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+
+            reader
+                .get_mut()
+                .write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+
+            (head, String::from_utf8(body).unwrap())
+        });
+
+        (url, handle)
+    }
+
+    #[test]
+    fn webhook_payload() {
+        let (url, server) = http_stand_in();
+
+        WebhookNotifier::new(url)
+            .notify("123456", &changes())
+            .unwrap();
+
+        let (head, body) = server.join().unwrap();
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+        assert!(head.starts_with("POST /hook"));
+        assert_eq!(
+            body["content"],
+            "New grades for 123456:\nEnglish 2 Adv: average 90 -> 92\n"
+        );
+        assert_eq!(body["text"], body["content"]);
+        assert_eq!(body["changes"]["averages_changed"][0]["new"], 92.0);
+    }
+
+    #[test]
+    fn ntfy_message() {
+        let (url, server) = http_stand_in();
+
+        NtfyNotifier::new(url).notify("123456", &changes()).unwrap();
+
+        let (head, body) = server.join().unwrap();
+
+        assert!(head.contains("title: New grades for 123456"));
+        assert_eq!(body, "English 2 Adv: average 90 -> 92\n");
+    }
+
+    #[test]
+    fn failing_notifier_is_reported() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        drop(listener);
+
+        let notifiers: Vec<Box<dyn Notifier>> = vec![Box::new(WebhookNotifier::new(url))];
+        let error = notify_all(&notifiers, "123456", &changes()).unwrap_err();
+
+        assert!(error.to_string().contains("webhook http://127.0.0.1"));
+    }
+
+    #[cfg(feature = "email")]
+    #[test]
+    fn smtp_email() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        // a minimal SMTP server that accepts one message and returns its DATA
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut writer = stream.try_clone().unwrap();
+            let mut reader = BufReader::new(stream);
+
+            writer.write_all(b"220 localhost ESMTP\r\n").unwrap();
+
+            let mut data = String::new();
+            let mut in_data = false;
+
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap() == 0 {
+                    break;
+                }
+
+                if in_data {
+                    if line == ".\r\n" {
+                        in_data = false;
+                        writer.write_all(b"250 queued\r\n").unwrap();
+                    } else {
+                        data.push_str(&line);
+                    }
+                    continue;
+                }
+
+                let reply: &[u8] = match &line.to_uppercase()[..4] {
+                    "EHLO" => b"250 localhost\r\n",
+                    "DATA" => {
+                        in_data = true;
+                        b"354 go ahead\r\n"
+                    }
+                    "QUIT" => {
+                        writer.write_all(b"221 bye\r\n").unwrap();
+                        break;
+                    }
+                    _ => b"250 ok\r\n",
+                };
+
+                writer.write_all(reply).unwrap();
+            }
+
+            data
+        });
+
+        SmtpNotifier::new(EmailConfig {
+            host: "127.0.0.1".to_owned(),
+            port,
+            username: None,
+            password: None,
+            from: "hac@example.com".to_owned(),
+            to: "student@example.com".to_owned(),
+            starttls: false,
+        })
+        .notify("123456", &changes())
+        .unwrap();
+
+        let data = server.join().unwrap();
+
+        assert!(data.contains("Subject: New grades for 123456"));
+        assert!(data.contains("English 2 Adv: average 90 -> 92"));
+    }
+
+    #[test]
+    fn config_file() {
+        let config: NotifierConfig = toml::from_str(
+            "stdout = true\n[[webhook]]\nurl = \"https://example.com/hook\"\n[[ntfy]]\nurl = \"https://ntfy.sh/grades\"\n",
+        )
+        .unwrap();
+
+        let names = config
+            .build()
+            .unwrap()
+            .iter()
+            .map(|n| n.name())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            names,
+            [
+                "stdout",
+                "webhook https://example.com/hook",
+                "ntfy https://ntfy.sh/grades"
+            ]
+        );
+    }
+}
@@ -0,0 +1,208 @@
+//! The district's rules for which courses count towards the GPA.
+
+use std::{fmt::Display, sync::LazyLock};
+
+use crate::{
+    catalog::{self, CourseId},
+    transcript::{Grade, Transcript, TranscriptEntry},
+};
+
+/// Why a course doesn't count towards the GPA.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ExclusionReason {
+    /// Graded pass/fail or credit/no credit (`P`, `F`, `CR` or `NC`).
+    PassFail,
+    /// Graded with some other mark (eg. incomplete or withdrawn).
+    Ungraded,
+    /// Earns local credit, which doesn't count towards graduation or the GPA.
+    LocalCredit,
+    /// Substitutes for PE (eg. marching band or athletics).
+    PeSubstitution,
+    /// Credit earned by exam.
+    CreditByExam,
+}
+
+impl Display for ExclusionReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self {
+            ExclusionReason::PassFail => "pass/fail",
+            ExclusionReason::Ungraded => "no numeric grade",
+            ExclusionReason::LocalCredit => "local credit",
+            ExclusionReason::PeSubstitution => "PE substitution",
+            ExclusionReason::CreditByExam => "credit by exam",
+        };
+
+        write!(f, "{reason}")
+    }
+}
+
+/// Excludes courses whose code is `code` or whose name contains the word(s) `keyword`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PolicyRule {
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub code: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub keyword: Option<String>,
+    pub reason: ExclusionReason,
+}
+
+impl PolicyRule {
+    fn matches(&self, entry: &TranscriptEntry) -> bool {
+        let code = || {
+            let expected = CourseId::parse(self.code.as_deref()?)?.code;
+            let actual = CourseId::parse(entry.code.as_deref()?)?.code;
+
+            Some(expected == actual)
+        };
+
+        (self.code.is_some() || self.keyword.is_some())
+            && (self.code.is_none() || code() == Some(true))
+            && self
+                .keyword
+                .as_ref()
+                .is_none_or(|keyword| catalog::has_words(&entry.name, keyword))
+    }
+}
+
+/// A course left out of the GPA.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExcludedCourse {
+    pub course: String,
+    pub grade: Grade,
+    /// The school year the grade was earned in, if known.
+    pub year: Option<String>,
+    pub reason: ExclusionReason,
+}
+
+/// Which transcript entries count towards the GPA. Entries graded with a mark never do; the rules
+/// exclude numerically graded courses too.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GpaPolicy {
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rules: Vec<PolicyRule>,
+}
+
+/// The bundled rules, as (name keywords, reason).
+const RULES: &[(&[&str], ExclusionReason)] = &[
+    (&["Local Credit", "LC"], ExclusionReason::LocalCredit),
+    (
+        &["PE Sub", "PE Substitution", "PES"],
+        ExclusionReason::PeSubstitution,
+    ),
+    (&["Credit by Exam", "CBE"], ExclusionReason::CreditByExam),
+];
+
+impl Default for GpaPolicy {
+    /// Frisco ISD's policy: local credit, PE substitutions and credit by exam don't count.
+    fn default() -> Self {
+        let rules = RULES
+            .iter()
+            .flat_map(|(keywords, reason)| {
+                keywords.iter().map(|keyword| PolicyRule {
+                    code: None,
+                    keyword: Some((*keyword).to_owned()),
+                    reason: *reason,
+                })
+            })
+            .collect();
+
+        Self { rules }
+    }
+}
+
+static BUNDLED: LazyLock<GpaPolicy> = LazyLock::new(GpaPolicy::default);
+
+impl GpaPolicy {
+    /// The bundled (district) policy, shared.
+    pub fn bundled() -> &'static GpaPolicy {
+        &BUNDLED
+    }
+
+    /// Why an entry doesn't count towards the GPA, or `None` if it does.
+    pub fn exclusion(&self, entry: &TranscriptEntry) -> Option<ExclusionReason> {
+        if let Some(rule) = self.rules.iter().find(|r| r.matches(entry)) {
+            return Some(rule.reason);
+        }
+
+        match entry.grade {
+            Grade::Numeric(_) => None,
+            Grade::Pass | Grade::Fail | Grade::Credit | Grade::NoCredit => {
+                Some(ExclusionReason::PassFail)
+            }
+            Grade::Incomplete | Grade::Withdrawn | Grade::Other(_) => {
+                Some(ExclusionReason::Ungraded)
+            }
+        }
+    }
+
+    /// Splits a transcript into the entries that count towards the GPA and the ones that don't.
+    pub fn apply(&self, transcript: &Transcript) -> (Transcript, Vec<ExcludedCourse>) {
+        let mut included = Vec::new();
+        let mut excluded = Vec::new();
+
+        for entry in &transcript.entries {
+            match self.exclusion(entry) {
+                Some(reason) => excluded.push(ExcludedCourse {
+                    course: entry.name.clone(),
+                    grade: entry.grade.clone(),
+                    year: entry.year.clone(),
+                    reason,
+                }),
+                None => included.push(entry.clone()),
+            }
+        }
+
+        (Transcript { entries: included }, excluded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcript::GpaScale;
+
+    #[test]
+    fn exclusions() {
+        let transcript = Transcript {
+            entries: vec![
+                TranscriptEntry::new("English 2 Adv".to_owned(), 95.0),
+                TranscriptEntry::new("Teen Leadership".to_owned(), Grade::Pass),
+                TranscriptEntry::new("Marching Band PE Sub".to_owned(), 100.0),
+                TranscriptEntry::new("Algebra 1 CBE".to_owned(), Grade::Credit),
+                TranscriptEntry::new("Study Skills LC".to_owned(), 90.0),
+                TranscriptEntry::new("Chemistry".to_owned(), Grade::Incomplete),
+            ],
+        };
+
+        let (included, excluded) = GpaPolicy::default().apply(&transcript);
+
+        assert_eq!(included.entries.len(), 1);
+        assert_eq!(included.gpa(), 5.0);
+
+        let reasons = excluded.iter().map(|e| e.reason).collect::<Vec<_>>();
+        assert_eq!(
+            reasons,
+            [
+                ExclusionReason::PassFail,
+                ExclusionReason::PeSubstitution,
+                ExclusionReason::CreditByExam,
+                ExclusionReason::LocalCredit,
+                ExclusionReason::Ungraded,
+            ]
+        );
+
+        assert_eq!(transcript.gpa(), 5.0);
+
+        // marks don't count, even without any rules
+        let no_rules = GpaPolicy { rules: Vec::new() };
+        assert_eq!(
+            transcript.gpa_with(&no_rules, GpaScale::Weighted),
+            (5.0 + 5.0 + 4.0) / 3.0
+        );
+    }
+}
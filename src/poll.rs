@@ -0,0 +1,302 @@
+//! Deciding when a long running watcher should next poll HAC.
+//!
+//! [`PollSchedule`] spreads polls out with random jitter, backs off exponentially while HAC is
+//! failing and never polls during quiet hours. [`WatchState`] is persisted between polls so that a
+//! restarted watcher picks up where the previous one left off instead of polling immediately.
+
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, NaiveDateTime, NaiveTime, TimeDelta, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// A daily window, in local time, during which nothing should be polled. The window may wrap past
+/// midnight (eg. `22:00-06:00`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl QuietHours {
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+
+    /// The end of the quiet window containing `at`, or `at` itself if it is outside the window.
+    pub fn end_after(&self, at: NaiveDateTime) -> NaiveDateTime {
+        if !self.contains(at.time()) {
+            return at;
+        }
+
+        let end = at.date().and_time(self.end);
+
+        if end > at {
+            end
+        } else {
+            end + TimeDelta::days(1)
+        }
+    }
+}
+
+impl FromStr for QuietHours {
+    type Err = anyhow::Error;
+
+    /// Parses `HH:MM-HH:MM`.
+    fn from_str(s: &str) -> Result<Self> {
+        let (start, end) = s.split_once('-').ok_or(anyhow!(
+            "expected quiet hours like `22:00-06:00`, got `{s}`"
+        ))?;
+
+        let parse = |time: &str| {
+            NaiveTime::parse_from_str(time.trim(), "%H:%M")
+                .with_context(|| format!("invalid time `{time}`"))
+        };
+
+        Ok(Self {
+            start: parse(start)?,
+            end: parse(end)?,
+        })
+    }
+}
+
+impl Display for QuietHours {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}-{}",
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )
+    }
+}
+
+/// How often to poll, and how to back off when polls fail.
+#[derive(Debug, Clone)]
+pub struct PollSchedule {
+    /// The delay between successful polls.
+    pub interval: Duration,
+    /// The delay after the first failure, doubled for every further consecutive failure.
+    pub retry: Duration,
+    /// The longest delay after failures.
+    pub max_backoff: Duration,
+    /// The fraction of each delay that is randomly added or removed, so that many watchers don't
+    /// all hit HAC at the same moment.
+    pub jitter: f64,
+    pub quiet_hours: Option<QuietHours>,
+}
+
+impl Default for PollSchedule {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(30 * 60),
+            retry: Duration::from_secs(60),
+            max_backoff: Duration::from_secs(2 * 60 * 60),
+            jitter: 0.1,
+            quiet_hours: None,
+        }
+    }
+}
+
+impl PollSchedule {
+    /// The delay before the next poll, without jitter or quiet hours, after `failures`
+    /// consecutive failed polls.
+    pub fn base_delay(&self, failures: u32) -> Duration {
+        if failures == 0 {
+            return self.interval;
+        }
+
+        let factor = 2u32.saturating_pow(failures - 1);
+
+        self.retry
+            .checked_mul(factor)
+            .unwrap_or(Duration::MAX)
+            .min(self.max_backoff)
+    }
+
+    /// The delay before the next poll after `failures` consecutive failed polls, where `now` is
+    /// the current local time. The delay is jittered and extended past any quiet hours it would
+    /// end in.
+    pub fn next_delay(&self, failures: u32, now: NaiveDateTime, rng: &mut impl Rng) -> Duration {
+        let base = self.base_delay(failures).as_secs_f64();
+        let jitter = match self.jitter {
+            jitter if jitter.is_nan() => 0.0,
+            jitter => jitter.clamp(0.0, 1.0),
+        };
+        let delay = Duration::from_secs_f64(base * rng.gen_range(1.0 - jitter..=1.0 + jitter));
+
+        self.after_quiet_hours(now, delay)
+    }
+
+    /// Extends `delay` so that the poll it schedules lands outside quiet hours.
+    pub fn after_quiet_hours(&self, now: NaiveDateTime, delay: Duration) -> Duration {
+        let Some(quiet_hours) = self.quiet_hours else {
+            return delay;
+        };
+
+        let Ok(delta) = TimeDelta::from_std(delay) else {
+            return delay;
+        };
+
+        let wake = quiet_hours.end_after(now + delta);
+
+        (wake - now).to_std().unwrap_or(delay)
+    }
+}
+
+/// What a watcher remembers between restarts.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WatchState {
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_attempt: Option<DateTime<Utc>>,
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+}
+
+impl WatchState {
+    /// The state file for a profile in the user's data directory (eg.
+    /// `~/.local/share/hac/watch-default.json`).
+    pub fn default_path(profile: &str) -> Option<PathBuf> {
+        dirs::data_local_dir().map(|dir| dir.join("hac").join(format!("watch-{profile}.json")))
+    }
+
+    /// Loads the state, or returns a fresh one if the file doesn't exist yet.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("could not read {}", path.display()))?;
+
+        serde_json::from_str(&contents).with_context(|| format!("invalid {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("could not write {}", path.display()))
+    }
+
+    pub fn record_success(&mut self, at: DateTime<Utc>) {
+        self.last_success = Some(at);
+        self.last_attempt = Some(at);
+        self.consecutive_failures = 0;
+        self.last_error = None;
+    }
+
+    pub fn record_failure(&mut self, at: DateTime<Utc>, error: &anyhow::Error) {
+        self.last_attempt = Some(at);
+        self.consecutive_failures += 1;
+        self.last_error = Some(format!("{error:#}"));
+    }
+
+    /// How long a restarted watcher should wait before its first poll, so that restarting doesn't
+    /// poll sooner than the schedule would have.
+    pub fn resume_delay(&self, schedule: &PollSchedule, now: DateTime<Utc>) -> Duration {
+        let Some(last_attempt) = self.last_attempt else {
+            return Duration::ZERO;
+        };
+
+        let due = TimeDelta::from_std(schedule.base_delay(self.consecutive_failures))
+            .map(|delay| last_attempt + delay)
+            .unwrap_or(now);
+
+        (due - now).to_std().unwrap_or(Duration::ZERO)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    fn at(hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 10, 1)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn backoff_and_quiet_hours() {
+        let schedule = PollSchedule {
+            interval: Duration::from_secs(30 * 60),
+            retry: Duration::from_secs(60),
+            max_backoff: Duration::from_secs(10 * 60),
+            jitter: 0.0,
+            quiet_hours: Some("22:00-06:00".parse().unwrap()),
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+
+        assert_eq!(schedule.base_delay(0), Duration::from_secs(30 * 60));
+        assert_eq!(schedule.base_delay(1), Duration::from_secs(60));
+        assert_eq!(schedule.base_delay(3), Duration::from_secs(4 * 60));
+        assert_eq!(schedule.base_delay(40), Duration::from_secs(10 * 60));
+
+        assert_eq!(
+            schedule.next_delay(0, at(12, 0), &mut rng),
+            Duration::from_secs(30 * 60)
+        );
+        // 21:45 + 30 minutes is quiet, so wait until 06:00
+        assert_eq!(
+            schedule.next_delay(0, at(21, 45), &mut rng),
+            Duration::from_secs((8 * 60 + 15) * 60)
+        );
+        assert_eq!(
+            schedule.next_delay(0, at(1, 0), &mut rng),
+            Duration::from_secs(5 * 60 * 60)
+        );
+
+        let jittered = PollSchedule {
+            jitter: 0.5,
+            quiet_hours: None,
+            ..schedule
+        };
+
+        for _ in 0..100 {
+            let delay = jittered.next_delay(0, at(12, 0), &mut rng).as_secs();
+            assert!((15 * 60..=45 * 60).contains(&delay));
+        }
+    }
+
+    #[test]
+    fn resumes_from_state() {
+        let schedule = PollSchedule::default();
+        let now = Utc::now();
+
+        assert_eq!(
+            WatchState::default().resume_delay(&schedule, now),
+            Duration::ZERO
+        );
+
+        let mut state = WatchState::default();
+        state.record_success(now - TimeDelta::minutes(10));
+        assert_eq!(
+            state.resume_delay(&schedule, now),
+            Duration::from_secs(20 * 60)
+        );
+
+        state.record_failure(now - TimeDelta::minutes(10), &anyhow!("offline"));
+        assert_eq!(state.resume_delay(&schedule, now), Duration::ZERO);
+        assert_eq!(state.last_error.as_deref(), Some("offline"));
+
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(serde_json::from_str::<WatchState>(&json).unwrap(), state);
+    }
+}
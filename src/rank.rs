@@ -0,0 +1,299 @@
+//! Estimating where class rank will move, from the official rank and the projected GPA.
+//!
+//! HAC only shows the student's own GPA and rank, so the rest of the class is modelled: their GPAs
+//! are assumed to be normally distributed with a given spread, centred so that the official GPA
+//! lands at the official rank. The model is fitted to the official GPA, so the student's projected
+//! GPA is the official one moved by however much this year's grades move the GPA this crate works
+//! out. Every estimate lists the assumptions it rests on.
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::{
+    catalog::Level,
+    policy::GpaPolicy,
+    transcript::{Transcript, TranscriptSummary},
+};
+
+/// The standard deviation of a class's weighted GPAs, in grade points, when it isn't known.
+pub const DEFAULT_GPA_SPREAD: f32 = 0.6;
+
+/// The class percentages thresholds are reported for.
+const THRESHOLDS: [f32; 2] = [10.0, 25.0];
+
+/// A rank threshold (eg. the top 10%) and what reaching it takes.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RankThreshold {
+    /// eg. 10 for the top 10%.
+    pub top_percent: f32,
+    /// The lowest rank inside the threshold.
+    pub rank: u32,
+    /// The estimated cumulative GPA at that rank.
+    pub gpa: f32,
+    /// Whether the projected GPA already reaches it.
+    pub met: bool,
+    /// The mean grade points every remaining course must earn to graduate at `gpa`, or `None`
+    /// if no courses remain.
+    pub needed_points: Option<f32>,
+    /// Whether `needed_points` can be earned at all (ie. is at most an AP 100's worth).
+    pub reachable: bool,
+}
+
+/// Where the student's rank is estimated to move.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RankProjection {
+    pub official_gpa: f32,
+    pub official_rank: u32,
+    pub class_size: u32,
+    /// The official GPA, moved by the difference this year's grades make to the weighted GPA.
+    pub projected_gpa: f32,
+    pub projected_rank: u32,
+    /// The percentage of the class at or above the projected rank.
+    pub projected_top_percent: f32,
+    /// The courses left before graduation.
+    pub remaining_courses: usize,
+    pub thresholds: Vec<RankThreshold>,
+    /// What the estimate assumes, in words.
+    pub assumptions: Vec<String>,
+}
+
+/// The standard normal cumulative distribution function, via Abramowitz and Stegun's 7.1.26
+/// approximation of erf (accurate to about 1e-7).
+fn normal_cdf(z: f64) -> f64 {
+    let x = z.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let erf = 1.0 - poly * (-x * x).exp();
+
+    if z >= 0.0 {
+        0.5 * (1.0 + erf)
+    } else {
+        0.5 * (1.0 - erf)
+    }
+}
+
+/// The inverse of [`normal_cdf`], by bisection.
+fn normal_quantile(p: f64) -> f64 {
+    let (mut low, mut high) = (-8.0, 8.0);
+
+    for _ in 0..64 {
+        let mid = (low + high) / 2.0;
+
+        if normal_cdf(mid) < p {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    (low + high) / 2.0
+}
+
+/// The class's GPAs, modelled as a normal distribution.
+struct ClassModel {
+    mean: f64,
+    spread: f64,
+    size: u32,
+}
+
+impl ClassModel {
+    /// Ranks are taken as the middle of their slice of the class, so that first and last place
+    /// don't sit at infinity.
+    fn top_fraction(&self, rank: u32) -> f64 {
+        (f64::from(rank) - 0.5) / f64::from(self.size)
+    }
+
+    fn fit(gpa: f32, rank: u32, size: u32, spread: f32) -> Self {
+        let mut model = Self {
+            mean: 0.0,
+            spread: f64::from(spread),
+            size,
+        };
+        let z = normal_quantile(1.0 - model.top_fraction(rank));
+        model.mean = f64::from(gpa) - z * model.spread;
+
+        model
+    }
+
+    fn rank_of(&self, gpa: f32) -> u32 {
+        let above = 1.0 - normal_cdf((f64::from(gpa) - self.mean) / self.spread);
+        let rank = (above * f64::from(self.size) + 0.5).round();
+
+        rank.clamp(1.0, f64::from(self.size)) as u32
+    }
+
+    fn gpa_at(&self, rank: u32) -> f32 {
+        (self.mean + normal_quantile(1.0 - self.top_fraction(rank)) * self.spread) as f32
+    }
+}
+
+/// Estimates the student's rank once the official GPA moves from the GPA of `posted` (the posted
+/// transcript) to the GPA of `projected` (the transcript combined with this year's grades), and
+/// what it takes to graduate in the top 10% and 25% with `remaining_courses` courses left. Both
+/// transcripts should be merged with [`MergeStrategy::Average`](crate::MergeStrategy::Average),
+/// so that every course is one entry, like each of the remaining courses. `spread` is the assumed
+/// standard deviation of the class's GPAs (see [`DEFAULT_GPA_SPREAD`]).
+pub fn project_rank(
+    summary: &TranscriptSummary,
+    posted: &Transcript,
+    projected: &Transcript,
+    remaining_courses: usize,
+    spread: f32,
+) -> Result<RankProjection> {
+    let official_gpa = summary
+        .gpa
+        .ok_or(anyhow!("the transcript doesn't show a cumulative GPA"))?;
+    let (Some(official_rank), Some(class_size)) = (summary.rank, summary.class_size) else {
+        bail!("the transcript doesn't show a class rank");
+    };
+
+    if class_size == 0 || official_rank == 0 || official_rank > class_size {
+        bail!("invalid class rank {official_rank} of {class_size}");
+    }
+    if spread <= 0.0 {
+        bail!("the GPA spread must be positive");
+    }
+
+    let model = ClassModel::fit(official_gpa, official_rank, class_size, spread);
+
+    // the entries the GPA is a mean of
+    let counted = GpaPolicy::bundled()
+        .apply(projected)
+        .0
+        .entries
+        .iter()
+        .filter(|e| e.gpa().is_some())
+        .count();
+    let (posted_gpa, current_gpa) = (posted.gpa(), projected.gpa());
+
+    if counted == 0 || posted_gpa.is_nan() || current_gpa.is_nan() {
+        bail!("the transcript has no graded courses to project from");
+    }
+
+    // the official GPA may be worked out a little differently, so only the change carries over
+    let projected_gpa = official_gpa + (current_gpa - posted_gpa);
+    let projected_rank = model.rank_of(projected_gpa);
+
+    let thresholds = THRESHOLDS
+        .iter()
+        .map(|&top_percent| {
+            let rank = ((class_size as f32 * top_percent / 100.0).floor() as u32).max(1);
+            let gpa = model.gpa_at(rank);
+
+            // the cumulative GPA is a mean over courses, so the remaining ones have to make up the
+            // difference between the projected GPA and the threshold
+            let needed_points = (remaining_courses > 0).then(|| {
+                let (taken, left) = (counted as f32, remaining_courses as f32);
+
+                (gpa * (taken + left) - projected_gpa * taken) / left
+            });
+
+            RankThreshold {
+                top_percent,
+                rank,
+                gpa,
+                met: projected_gpa >= gpa,
+                needed_points,
+                reachable: needed_points.is_none_or(|p| p <= Level::Ap.weightage()),
+            }
+        })
+        .collect();
+
+    let mut assumptions = vec![
+        format!(
+            "Classmates' weighted GPAs are normally distributed with a standard deviation of \
+             {spread} grade points, centred so that a {official_gpa} GPA ranks \
+             {official_rank} of {class_size}."
+        ),
+        "Classmates' GPAs stay where they were when the transcript was posted; only this \
+         student's GPA moves."
+            .to_owned(),
+        format!("The class stays at {class_size} students, and ties are ignored."),
+        "This year's grades so far count as if they were final.".to_owned(),
+        format!(
+            "This year's grades move the official {official_gpa} GPA as much as they move this \
+             tool's {posted_gpa:.3} GPA of the posted transcript (to {current_gpa:.3})."
+        ),
+    ];
+
+    if remaining_courses > 0 {
+        assumptions.push(format!(
+            "{remaining_courses} courses remain, each counting towards the GPA like the \
+             {counted} counted so far."
+        ));
+    }
+
+    Ok(RankProjection {
+        official_gpa,
+        official_rank,
+        class_size,
+        projected_gpa,
+        projected_rank,
+        projected_top_percent: projected_rank as f32 / class_size as f32 * 100.0,
+        remaining_courses,
+        thresholds,
+        assumptions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcript::TranscriptEntry;
+
+    #[test]
+    fn projection() {
+        assert!((normal_cdf(1.96) - 0.975).abs() < 1e-4);
+        assert!((normal_quantile(0.5)).abs() < 1e-6);
+
+        let summary = TranscriptSummary {
+            gpa: Some(4.8),
+            rank: Some(150),
+            class_size: Some(1000),
+        };
+        // this year's grades raise the tool's GPA by 0.4
+        let posted = Transcript {
+            entries: (0..2)
+                .map(|i| TranscriptEntry::new(format!("Class {i}"), 96.0))
+                .collect(),
+        };
+        let projected = Transcript {
+            entries: (0..4)
+                .map(|i| TranscriptEntry::new(format!("Class {i}"), 100.0))
+                .collect(),
+        };
+
+        // the official GPA ranks where it officially did
+        let model = ClassModel::fit(4.8, 150, 1000, DEFAULT_GPA_SPREAD);
+        assert_eq!(model.rank_of(4.8), 150);
+
+        let projection =
+            project_rank(&summary, &posted, &projected, 4, DEFAULT_GPA_SPREAD).unwrap();
+
+        assert!((projection.projected_gpa - 5.2).abs() < 1e-4);
+        assert!(projection.projected_rank < 150);
+
+        let [top_10, top_25] = &projection.thresholds[..] else {
+            panic!("expected two thresholds");
+        };
+
+        assert_eq!((top_10.rank, top_25.rank), (100, 250));
+        assert!(top_10.gpa > 4.8 && top_25.gpa < 4.8);
+        assert!(top_25.met);
+        // half the courses are left, so they must average twice the gap to the threshold
+        let needed = top_10.needed_points.unwrap();
+        assert!((needed - (2.0 * top_10.gpa - 5.2)).abs() < 1e-4);
+        assert!(top_10.reachable);
+
+        assert!(project_rank(&TranscriptSummary::default(), &posted, &projected, 4, 0.6).is_err());
+
+        // nothing graded to take a GPA of
+        let empty = Transcript {
+            entries: Vec::new(),
+        };
+        assert!(project_rank(&summary, &posted, &empty, 4, 0.6).is_err());
+    }
+}
@@ -1,27 +1,43 @@
-use std::fmt::Display;
+use std::{cmp::Ordering, convert::Infallible, fmt::Display, str::FromStr};
 
-use anyhow::{anyhow, bail, Result};
+use anyhow::{anyhow, Result};
+use chrono::Weekday;
 use reqwest::blocking::Client;
 use scraper::Html;
 
-use crate::selector;
+use crate::{
+    catalog::{Catalog, Classification, CourseId},
+    client, html, selector,
+    teachers::Teacher,
+};
 
 const SCHEDULE_PAGE_URL: &str = "https://hac.friscoisd.org/HomeAccess/Content/Student/Classes.aspx";
 
-/// A course a student is currently enrolled in, for this academic year.
-#[derive(Debug, Clone)]
+/// A course a student is enrolled in, for this academic year.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Course {
     /// The common name of the course (eg. "English 2 Adv").
     name: String,
     /// The internal HAC id of the course.
     id: String,
 
-    /// The period of the day the course is present.
-    period: Period,
-    /// The course's teacher.
-    teacher: String,
-    /// The classroom the course is taught in.
+    /// Every period the course meets in, in the order HAC lists them. Never empty when parsed
+    /// from HAC, but may be in deserialized data.
+    periods: Vec<Period>,
+    /// The course's teacher, in its first meeting. See [`Course::teacher_in`].
+    teacher: Teacher,
+    /// The classroom the course is taught in, in its first meeting. See
+    /// [`Course::classroom_in`].
     classroom: String,
+    /// The campus the course is taught at, if HAC lists one.
+    #[cfg_attr(feature = "serde", serde(default))]
+    building: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    status: CourseStatus,
+    /// The marking periods the course spans (eg. `["Q1", "Q2"]` or `["S1"]`).
+    #[cfg_attr(feature = "serde", serde(default))]
+    marking_periods: Vec<String>,
 }
 
 impl Course {
@@ -33,80 +49,222 @@ impl Course {
         &self.id
     }
 
-    pub fn period(&self) -> &Period {
-        &self.period
+    /// The course code, section and semester parsed from the id.
+    pub fn course_id(&self) -> Option<CourseId> {
+        CourseId::parse(&self.id)
     }
 
-    pub fn teacher(&self) -> &str {
+    /// The course's subject area and level, according to `catalog`.
+    pub fn classify(&self, catalog: &Catalog) -> Classification {
+        catalog.classify(self.course_id().as_ref(), &self.name)
+    }
+
+    /// The first period the course meets in. See [`Course::periods`] for courses that meet more
+    /// than once.
+    pub fn period(&self) -> Option<&Period> {
+        self.periods.first()
+    }
+
+    pub fn periods(&self) -> &[Period] {
+        &self.periods
+    }
+
+    pub fn teacher(&self) -> &Teacher {
         &self.teacher
     }
 
     pub fn classroom(&self) -> &str {
         &self.classroom
     }
+
+    /// The teacher in one of the course's meetings, which can differ from the first meeting's.
+    pub fn teacher_in<'a>(&'a self, period: &'a Period) -> &'a Teacher {
+        if period.teacher.name.is_empty() {
+            &self.teacher
+        } else {
+            &period.teacher
+        }
+    }
+
+    /// The classroom of one of the course's meetings (eg. a lab on B days), which can differ from
+    /// the first meeting's.
+    pub fn classroom_in<'a>(&'a self, period: &'a Period) -> &'a str {
+        if period.classroom.is_empty() {
+            &self.classroom
+        } else {
+            &period.classroom
+        }
+    }
+
+    pub fn building(&self) -> Option<&str> {
+        self.building.as_deref()
+    }
+
+    pub fn status(&self) -> CourseStatus {
+        self.status
+    }
+
+    pub fn is_dropped(&self) -> bool {
+        self.status == CourseStatus::Dropped
+    }
+
+    pub fn marking_periods(&self) -> &[String] {
+        &self.marking_periods
+    }
+
+    /// Whether the course runs in `semester` (1 or 2), going by its marking periods. Courses
+    /// whose marking periods aren't listed (or recognized) are taken to run all year.
+    pub fn in_semester(&self, semester: u8) -> bool {
+        let semester_of = |period: &str| match period.to_ascii_uppercase().as_str() {
+            "S1" | "Q1" | "Q2" => Some(1),
+            "S2" | "Q3" | "Q4" => Some(2),
+            _ => None,
+        };
+
+        let semesters = self
+            .marking_periods
+            .iter()
+            .filter_map(|p| semester_of(p))
+            .collect::<Vec<_>>();
+
+        semesters.is_empty() || semesters.contains(&semester)
+    }
+}
+
+/// Whether the student is still enrolled in a course.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CourseStatus {
+    #[default]
+    Active,
+    Dropped,
 }
 
-/// A period of the day, in block schedule where the `period_number` ranges from
-/// 1-4 and the day is either `A` or `B`.
-#[derive(Debug, Clone)]
+/// A period of the day: which period (eg. `2`, `1-2` or `ADV`) on which days (eg. `A`, `AB` or
+/// `M,W,F`), and where and with whom the course meets then.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Period {
     number: PeriodNumber,
     day: Day,
+    classroom: String,
+    teacher: Teacher,
 }
 
 /// The period number of a course in a day. For most courses, this will range from
-/// 1 to 4, with the exception of some which take place in `ADV` (advisory).
-#[derive(Debug, Clone)]
+/// 1 to 4, with the exception of some which take place in `ADV` (advisory) or span several
+/// periods (eg. `1-2`).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum PeriodNumber {
     Number(u32),
+    /// Consecutive periods, inclusive (eg. `1-2` for a double block).
+    Range(u32, u32),
+    Advisory,
     Unknown(String),
 }
 
-#[derive(Debug, Clone)]
+/// The days a course meets on. Block schedules use `A` and `B` days; traditional schedules list
+/// weekdays.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Day {
     A,
     B,
+    /// Both A and B days (`AB`), ie. every day.
+    Both,
+    /// Specific days of the week (eg. `M,W,F` or `M-F`), in order.
+    Weekdays(Vec<Weekday>),
+    /// A pattern that couldn't be parsed, kept as HAC showed it.
+    Unknown(String),
 }
 
 pub fn get_schedule(client: &Client) -> Result<Vec<Course>> {
-    let resp = client.get(SCHEDULE_PAGE_URL).send()?.text()?;
-    let document = Html::parse_document(&resp);
-
-    let mut courses = Vec::new();
-
-    for class in document.select(selector!(
-        "#plnMain_dgSchedule > tbody > tr.sg-asp-table-data-row"
-    )) {
-        let mut elements = class.select(selector!("td")).map(|c| {
-            let first_element = c
-                .children()
-                .find(|child| child.value().as_element().is_some());
-
-            let text = match first_element {
-                None => c.text().next(),
-                Some(e) => e
-                    .first_child()
-                    .and_then(|e| e.value().as_text().map(|text| &**text)),
-            };
-
-            text.unwrap_or("").trim().to_owned()
-        });
+    let resp = client::fetch_page(client.get(SCHEDULE_PAGE_URL))?;
+
+    parse_schedule(&Html::parse_document(&resp))
+}
+
+/// Reads the schedule table by its column headers. A course that meets in several periods is
+/// listed once per meeting; those rows are merged into one course.
+pub(crate) fn parse_schedule(document: &Html) -> Result<Vec<Course>> {
+    let table = document
+        .select(selector!("#plnMain_dgSchedule"))
+        .next()
+        .ok_or(anyhow!("missing schedule table"))?;
+
+    let headers = table
+        .select(selector!("tr.sg-asp-table-header-row"))
+        .next()
+        .map(html::header_names)
+        .unwrap_or_default();
+
+    let column = |names: &[&str]| names.iter().find_map(|name| html::column(&headers, name));
+    let required = |names: &[&str]| column(names).ok_or(anyhow!("missing {} column", names[0]));
 
-        let id = elements.next().ok_or(anyhow!("missing course id"))?;
-        let name = elements.next().ok_or(anyhow!("missing course name"))?;
-        let period_number = elements.next().ok_or(anyhow!("missing period number"))?;
-        let teacher = elements.next().ok_or(anyhow!("missing teacher"))?;
-        let classroom = elements.next().ok_or(anyhow!("missing classroom"))?;
-        let day = elements.next().ok_or(anyhow!("missing day"))?;
+    let id = required(&["Course"])?;
+    let name = required(&["Description"])?;
+    let period_number = required(&["Periods", "Period"])?;
+    let teacher = required(&["Teacher"])?;
+    let classroom = required(&["Room"])?;
+    let day = required(&["Days", "Day"])?;
+    let building = column(&["Building"]);
+    let status = column(&["Status"]);
+    let marking_periods = column(&["Marking Periods"]);
 
-        let period = Period::from_elements(&period_number, &day)?;
+    let mut courses: Vec<Course> = Vec::new();
+
+    for row in table.select(selector!("tr.sg-asp-table-data-row")) {
+        let elements = row.select(selector!("td")).collect::<Vec<_>>();
+        let cells = elements.iter().map(|e| html::text(*e)).collect::<Vec<_>>();
+        let cell = |index: Option<usize>| {
+            index
+                .and_then(|i| cells.get(i))
+                .map(String::as_str)
+                .unwrap_or("")
+        };
+
+        let id = cell(Some(id)).to_owned();
+        let period = Period {
+            classroom: cell(Some(classroom)).to_owned(),
+            teacher: elements
+                .get(teacher)
+                .map(|cell| Teacher::from_cell(*cell))
+                .unwrap_or_default(),
+            ..Period::from_elements(cell(Some(period_number)), cell(Some(day)))
+        };
+
+        if let Some(course) = courses.iter_mut().find(|c| c.id == id) {
+            if !course.periods.contains(&period) {
+                course.periods.push(period);
+            }
+
+            continue;
+        }
+
+        let status = if cell(status).to_lowercase().contains("drop") {
+            CourseStatus::Dropped
+        } else {
+            CourseStatus::Active
+        };
 
         courses.push(Course {
             id,
-            name,
-            period,
-            teacher,
-            classroom,
+            name: cell(Some(name)).to_owned(),
+            teacher: period.teacher.clone(),
+            classroom: period.classroom.clone(),
+            periods: vec![period],
+            building: Some(cell(building))
+                .filter(|b| !b.is_empty())
+                .map(str::to_owned),
+            status,
+            marking_periods: cell(marking_periods)
+                .split([',', ' '])
+                .filter(|mp| !mp.is_empty())
+                .map(str::to_owned)
+                .collect(),
         });
     }
 
@@ -114,35 +272,348 @@ pub fn get_schedule(client: &Client) -> Result<Vec<Course>> {
 }
 
 impl Period {
-    pub fn from_elements(number: &str, day: &str) -> Result<Self> {
-        let number = match number.trim().parse::<u32>() {
-            Ok(number) => PeriodNumber::Number(number),
-            Err(_) => PeriodNumber::Unknown(number.trim_end().to_owned()),
-        };
+    pub fn number(&self) -> &PeriodNumber {
+        &self.number
+    }
+
+    pub fn day(&self) -> &Day {
+        &self.day
+    }
+
+    /// Parses the period and day columns of the schedule. Anything unrecognized is kept as
+    /// [`PeriodNumber::Unknown`] or [`Day::Unknown`] rather than failing.
+    pub fn from_elements(number: &str, day: &str) -> Self {
+        Self {
+            number: number.parse().unwrap(),
+            day: day.parse().unwrap(),
+            classroom: String::new(),
+            teacher: Teacher::default(),
+        }
+    }
+}
+
+impl FromStr for PeriodNumber {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Infallible> {
+        let s = s.trim();
+
+        if let Ok(number) = s.parse() {
+            return Ok(PeriodNumber::Number(number));
+        }
+
+        if let Some((start, end)) = s.split_once('-') {
+            if let (Ok(start), Ok(end)) = (start.trim().parse(), end.trim().parse()) {
+                return Ok(PeriodNumber::Range(start, end));
+            }
+        }
+
+        if s.eq_ignore_ascii_case("ADV") || s.eq_ignore_ascii_case("Advisory") {
+            return Ok(PeriodNumber::Advisory);
+        }
+
+        Ok(PeriodNumber::Unknown(s.to_owned()))
+    }
+}
 
-        let day = match day.chars().next() {
-            Some('A') => Day::A,
-            Some('B') => Day::B,
-            _ => bail!("invalid day, {day}"),
+impl FromStr for Day {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Infallible> {
+        let pattern = s.trim().to_uppercase();
+        // "A Day" and "B-Day", but not "Monday"
+        let rotation = pattern
+            .strip_suffix("DAY")
+            .unwrap_or(&pattern)
+            .trim_end_matches([' ', '-'])
+            .replace([' ', ',', '/', '&'], "");
+
+        let day = match rotation.as_str() {
+            "A" => Day::A,
+            "B" => Day::B,
+            "AB" | "BA" => Day::Both,
+            _ => match parse_weekdays(&pattern) {
+                Some(weekdays) => Day::Weekdays(weekdays),
+                None => Day::Unknown(s.trim().to_owned()),
+            },
         };
 
-        Ok(Self { number, day })
+        Ok(day)
+    }
+}
+
+/// Parses weekday lists like `M,W,F`, `MWF`, `TTh`, `M-F`, `Mon, Wed` or `Tuesday/Thursday`.
+fn parse_weekdays(pattern: &str) -> Option<Vec<Weekday>> {
+    let mut days = Vec::new();
+
+    for token in pattern.split([',', ' ', '/']).filter(|t| !t.is_empty()) {
+        if let Some((start, end)) = token.split_once('-') {
+            let (mut day, end) = (weekday(start)?, weekday(end)?);
+
+            days.push(day);
+            while day != end {
+                day = day.succ();
+                days.push(day);
+            }
+        } else if let Some(day) = weekday_name(token) {
+            days.push(day);
+        } else {
+            let mut rest = token;
+
+            while !rest.is_empty() {
+                let (day, len) = weekday_letters(rest)?;
+                days.push(day);
+                rest = &rest[len..];
+            }
+        }
+    }
+
+    days.sort_by_key(Weekday::num_days_from_monday);
+    days.dedup();
+
+    (!days.is_empty()).then_some(days)
+}
+
+fn weekday(token: &str) -> Option<Weekday> {
+    weekday_name(token).or_else(|| {
+        weekday_letters(token)
+            .filter(|(_, len)| *len == token.len())
+            .map(|(day, _)| day)
+    })
+}
+
+/// Reads a whole (uppercase) weekday name or one of its usual abbreviations (eg. `THURS`).
+fn weekday_name(token: &str) -> Option<Weekday> {
+    let day = match token {
+        "MON" | "MONDAY" => Weekday::Mon,
+        "TUE" | "TUES" | "TUESDAY" => Weekday::Tue,
+        "WED" | "WEDS" | "WEDNESDAY" => Weekday::Wed,
+        "THU" | "THUR" | "THURS" | "THURSDAY" => Weekday::Thu,
+        "FRI" | "FRIDAY" => Weekday::Fri,
+        "SAT" | "SATURDAY" => Weekday::Sat,
+        "SUN" | "SUNDAY" => Weekday::Sun,
+        _ => return None,
+    };
+
+    Some(day)
+}
+
+/// Reads the one or two letter weekday abbreviation at the start of `s`, returning the day and
+/// the number of bytes it used.
+fn weekday_letters(s: &str) -> Option<(Weekday, usize)> {
+    for (prefix, day) in [
+        ("TH", Weekday::Thu),
+        ("SA", Weekday::Sat),
+        ("SU", Weekday::Sun),
+    ] {
+        if s.starts_with(prefix) {
+            return Some((day, 2));
+        }
+    }
+
+    let day = match s.chars().next()? {
+        'M' => Weekday::Mon,
+        'T' => Weekday::Tue,
+        'W' => Weekday::Wed,
+        'R' => Weekday::Thu,
+        'F' => Weekday::Fri,
+        'S' => Weekday::Sat,
+        'U' => Weekday::Sun,
+        _ => return None,
+    };
+
+    Some((day, 1))
+}
+
+impl Day {
+    /// Whether a course with this day pattern meets on a day with the given rotation day (eg.
+    /// A or B) and weekday.
+    pub fn meets(&self, rotation: Option<&Day>, weekday: Weekday) -> bool {
+        match self {
+            Day::A | Day::B => rotation == Some(self),
+            Day::Both => true,
+            Day::Weekdays(days) => days.contains(&weekday),
+            Day::Unknown(_) => false,
+        }
+    }
+
+    /// A key that orders A and B days first, then weekday patterns by their first day.
+    fn sort_key(&self) -> (u8, Vec<u32>, &str) {
+        match self {
+            Day::A => (0, Vec::new(), ""),
+            Day::B => (1, Vec::new(), ""),
+            Day::Both => (2, Vec::new(), ""),
+            Day::Weekdays(days) => (
+                3,
+                days.iter().map(Weekday::num_days_from_monday).collect(),
+                "",
+            ),
+            Day::Unknown(raw) => (4, Vec::new(), raw),
+        }
+    }
+}
+
+impl PartialOrd for Day {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Day {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sort_key().cmp(&other.sort_key())
     }
 }
 
 impl Display for Period {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}{}",
-            match &self.number {
-                PeriodNumber::Number(n) => n.to_string(),
-                PeriodNumber::Unknown(u) => u.clone(),
-            },
-            match self.day {
-                Day::A => "A",
-                Day::B => "B",
+        match self.day {
+            Day::A | Day::B | Day::Both => write!(f, "{}{}", self.number, self.day),
+            _ => write!(f, "{} {}", self.number, self.day),
+        }
+    }
+}
+
+impl Display for PeriodNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PeriodNumber::Number(n) => write!(f, "{n}"),
+            PeriodNumber::Range(start, end) => write!(f, "{start}-{end}"),
+            PeriodNumber::Advisory => write!(f, "ADV"),
+            PeriodNumber::Unknown(u) => write!(f, "{u}"),
+        }
+    }
+}
+
+impl Display for Day {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Day::A => write!(f, "A"),
+            Day::B => write!(f, "B"),
+            Day::Both => write!(f, "AB"),
+            Day::Weekdays(days) => {
+                let letters = days
+                    .iter()
+                    .map(|day| match day {
+                        Weekday::Mon => "M",
+                        Weekday::Tue => "T",
+                        Weekday::Wed => "W",
+                        Weekday::Thu => "Th",
+                        Weekday::Fri => "F",
+                        Weekday::Sat => "Sa",
+                        Weekday::Sun => "Su",
+                    })
+                    .collect::<Vec<_>>();
+
+                write!(f, "{}", letters.join(","))
             }
-        )
+            Day::Unknown(raw) => write!(f, "{raw}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"
+        <table id="plnMain_dgSchedule" class="sg-asp-table">
+            <tr class="sg-asp-table-header-row">
+                <td>Course</td><td>Description</td><td>Periods</td><td>Teacher</td><td>Room</td>
+                <td>Days</td><td>Marking Periods</td><td>Building</td><td>Status</td>
+            </tr>
+            <tr class="sg-asp-table-data-row">
+                <td>ENG2200A - 3</td><td><a>English 2 Adv</a></td><td>2</td>
+                <td><a href="mailto:jane.smith@friscoisd.org">Smith, Jane</a></td><td>B204</td>
+                <td>A</td><td>Q1, Q2, Q3, Q4</td><td>Heritage High School</td><td>Active</td>
+            </tr>
+            <tr class="sg-asp-table-data-row">
+                <td>CHM1100A - 1</td><td>Chemistry Adv</td><td>3</td><td>Lee, Sam</td><td>C110</td>
+                <td>A</td><td>S1</td><td>Heritage High School</td><td>Dropped</td>
+            </tr>
+            <tr class="sg-asp-table-data-row">
+                <td>ENG2200A - 3</td><td>English 2 Adv</td><td>4</td><td>Smith, Jane</td>
+                <td>B110</td><td>B</td><td>Q1, Q2, Q3, Q4</td><td>Heritage High School</td>
+                <td>Active</td>
+            </tr>
+        </table>
+    "#;
+
+    #[test]
+    fn schedule_page() {
+        let courses = parse_schedule(&Html::parse_document(PAGE)).unwrap();
+
+        assert_eq!(courses.len(), 2);
+
+        let english = &courses[0];
+        assert_eq!(english.id(), "ENG2200A - 3");
+        assert_eq!(english.name(), "English 2 Adv");
+        assert_eq!(english.teacher().name, "Smith, Jane");
+        assert_eq!(
+            english.teacher().email.as_deref(),
+            Some("jane.smith@friscoisd.org")
+        );
+        assert_eq!(english.classroom(), "B204");
+        assert_eq!(english.building(), Some("Heritage High School"));
+        assert_eq!(english.marking_periods(), ["Q1", "Q2", "Q3", "Q4"]);
+        assert_eq!(
+            english
+                .periods()
+                .iter()
+                .map(Period::to_string)
+                .collect::<Vec<_>>(),
+            ["2A", "4B"]
+        );
+        // each meeting keeps its own room
+        let b_day = &english.periods()[1];
+        assert_eq!(english.classroom_in(b_day), "B110");
+        assert_eq!(english.teacher_in(b_day).name, "Smith, Jane");
+
+        assert!(!english.is_dropped());
+        assert!(courses[1].is_dropped());
+        assert_eq!(courses[1].marking_periods(), ["S1"]);
+    }
+
+    #[test]
+    fn period_patterns() {
+        let period = |number: &str, day: &str| Period::from_elements(number, day);
+
+        assert_eq!(period("2", "A").to_string(), "2A");
+        assert_eq!(period("1-2", "AB").day(), &Day::Both);
+        assert_eq!(period("1-2", "AB").number(), &PeriodNumber::Range(1, 2));
+        assert_eq!(period("ADV", "B").number(), &PeriodNumber::Advisory);
+        assert_eq!(period("0", "B Day").to_string(), "0B");
+
+        use Weekday::*;
+        assert_eq!(
+            period("3", "M,W,F").day(),
+            &Day::Weekdays(vec![Mon, Wed, Fri])
+        );
+        assert_eq!(period("3", "TTh").day(), &Day::Weekdays(vec![Tue, Thu]));
+        assert_eq!(
+            period("3", "M-F").day(),
+            &Day::Weekdays(vec![Mon, Tue, Wed, Thu, Fri])
+        );
+        assert_eq!(period("3", "Mon, Wed").to_string(), "3 M,W");
+        assert_eq!(period("3", "Thursday").day(), &Day::Weekdays(vec![Thu]));
+        assert_eq!(
+            period("3", "Tuesday/Thursday").day(),
+            &Day::Weekdays(vec![Tue, Thu])
+        );
+        assert_eq!(
+            period("3", "Monday-Friday").day(),
+            &Day::Weekdays(vec![Mon, Tue, Wed, Thu, Fri])
+        );
+        assert_eq!(
+            period("3", "Tues, Thurs").day(),
+            &Day::Weekdays(vec![Tue, Thu])
+        );
+
+        assert_eq!(
+            period("Lunch", "??").number(),
+            &PeriodNumber::Unknown("Lunch".to_owned())
+        );
+        assert_eq!(period("4", "Z").day(), &Day::Unknown("Z".to_owned()));
+        assert!(Day::A < Day::Weekdays(vec![Mon]));
     }
 }
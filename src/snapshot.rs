@@ -0,0 +1,52 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+use crate::{
+    assignments::ClassAssignments,
+    client::Client,
+    schedule::Course,
+    transcript::{Transcript, TranscriptEntry},
+};
+
+/// Everything HAC showed for a student at one point in time.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snapshot {
+    /// The username of the student the snapshot belongs to.
+    pub student: String,
+    pub taken_at: DateTime<Utc>,
+    pub schedule: Vec<Course>,
+    /// The report card run (quarter) `classes` is from.
+    pub run: u8,
+    /// Every class's average and assignments for `run`.
+    pub classes: Vec<ClassAssignments>,
+    /// The most recently posted transcript.
+    pub transcript: Transcript,
+}
+
+impl Snapshot {
+    /// Fetches a new snapshot, using the latest report card run that has any grades entered.
+    pub fn take(client: &Client) -> Result<Self> {
+        let (run, classes) = client.get_current_assignments()?;
+
+        Ok(Self {
+            student: client.username().to_owned(),
+            taken_at: Utc::now(),
+            schedule: client.get_schedule()?,
+            run,
+            classes,
+            transcript: client.get_transcript()?,
+        })
+    }
+
+    /// The class averages for the snapshot's run, skipping classes without one.
+    pub fn grades(&self) -> Transcript {
+        let entries = self
+            .classes
+            .iter()
+            .filter_map(|c| Some(TranscriptEntry::new(c.course.clone(), c.average?)))
+            .collect();
+
+        Transcript { entries }
+    }
+}
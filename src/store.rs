@@ -0,0 +1,235 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use crate::snapshot::Snapshot;
+
+/// The current version of the database schema, stored in `PRAGMA user_version`.
+const SCHEMA_VERSION: i32 = 1;
+
+const SCHEMA: &str = "
+    CREATE TABLE snapshots (
+        id INTEGER PRIMARY KEY,
+        student TEXT NOT NULL,
+        taken_at TEXT NOT NULL,
+        run INTEGER NOT NULL,
+        schedule TEXT NOT NULL,
+        classes TEXT NOT NULL,
+        transcript TEXT NOT NULL
+    );
+
+    CREATE INDEX snapshots_by_student ON snapshots (student, taken_at);
+";
+
+/// A local SQLite database of snapshots, for any number of students. Each part of a snapshot is
+/// stored as JSON, in the same shape as the CLI's JSON output.
+pub struct SnapshotStore {
+    connection: Connection,
+}
+
+/// A stored snapshot, along with its id in the store.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredSnapshot {
+    pub id: i64,
+    pub snapshot: Snapshot,
+}
+
+impl SnapshotStore {
+    /// Opens (creating if needed) the database at `path`.
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let connection = Connection::open(path)
+            .with_context(|| format!("could not open database {}", path.display()))?;
+
+        Self::init(connection)
+    }
+
+    /// Opens a temporary database that only lives as long as the store.
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    /// The default database location (eg. `~/.local/share/hac/history.sqlite3`).
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::data_local_dir().map(|dir| dir.join("hac").join("history.sqlite3"))
+    }
+
+    fn init(connection: Connection) -> Result<Self> {
+        let version: i32 = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+        match version {
+            0 => {
+                connection.execute_batch(SCHEMA)?;
+                connection.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+            }
+            SCHEMA_VERSION => {}
+            other => bail!("unsupported database schema version {other}"),
+        }
+
+        Ok(Self { connection })
+    }
+
+    /// Stores a snapshot, returning its id.
+    pub fn save(&self, snapshot: &Snapshot) -> Result<i64> {
+        self.connection.execute(
+            "INSERT INTO snapshots (student, taken_at, run, schedule, classes, transcript)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                snapshot.student,
+                snapshot.taken_at.to_rfc3339(),
+                snapshot.run,
+                serde_json::to_string(&snapshot.schedule)?,
+                serde_json::to_string(&snapshot.classes)?,
+                serde_json::to_string(&snapshot.transcript)?,
+            ],
+        )?;
+
+        Ok(self.connection.last_insert_rowid())
+    }
+
+    pub fn get(&self, id: i64) -> Result<Option<StoredSnapshot>> {
+        self.connection
+            .query_row(
+                "SELECT * FROM snapshots WHERE id = ?1",
+                [id],
+                Self::read_row,
+            )
+            .optional()?
+            .transpose()
+    }
+
+    /// The most recent snapshot of the student, if any.
+    pub fn latest(&self, student: &str) -> Result<Option<StoredSnapshot>> {
+        self.connection
+            .query_row(
+                "SELECT * FROM snapshots WHERE student = ?1 ORDER BY taken_at DESC, id DESC LIMIT 1",
+                [student],
+                Self::read_row,
+            )
+            .optional()?
+            .transpose()
+    }
+
+    /// The most recent snapshot of the student taken at or before `time`, if any.
+    pub fn latest_before(
+        &self,
+        student: &str,
+        time: DateTime<Utc>,
+    ) -> Result<Option<StoredSnapshot>> {
+        self.connection
+            .query_row(
+                "SELECT * FROM snapshots WHERE student = ?1 AND taken_at <= ?2
+                 ORDER BY taken_at DESC, id DESC LIMIT 1",
+                params![student, time.to_rfc3339()],
+                Self::read_row,
+            )
+            .optional()?
+            .transpose()
+    }
+
+    /// Every snapshot of the student, oldest first.
+    pub fn history(&self, student: &str) -> Result<Vec<StoredSnapshot>> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT * FROM snapshots WHERE student = ?1 ORDER BY taken_at, id")?;
+
+        let rows = statement.query_map([student], Self::read_row)?;
+
+        rows.map(|row| row?).collect()
+    }
+
+    fn read_row(row: &Row) -> rusqlite::Result<Result<StoredSnapshot>> {
+        let id: i64 = row.get("id")?;
+        let student: String = row.get("student")?;
+        let taken_at: String = row.get("taken_at")?;
+        let run: u8 = row.get("run")?;
+        let schedule: String = row.get("schedule")?;
+        let classes: String = row.get("classes")?;
+        let transcript: String = row.get("transcript")?;
+
+        let parse = || -> Result<StoredSnapshot> {
+            Ok(StoredSnapshot {
+                id,
+                snapshot: Snapshot {
+                    student,
+                    taken_at: DateTime::parse_from_rfc3339(&taken_at)?.to_utc(),
+                    run,
+                    schedule: serde_json::from_str(&schedule)?,
+                    classes: serde_json::from_str(&classes)?,
+                    transcript: serde_json::from_str(&transcript)?,
+                },
+            })
+        };
+
+        Ok(parse().with_context(|| format!("corrupt snapshot #{id}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::{
+        assignments::{Assignment, AssignmentStatus, ClassAssignments},
+        transcript::{Transcript, TranscriptEntry},
+    };
+
+    fn snapshot(student: &str, day: u32, average: f32) -> Snapshot {
+        Snapshot {
+            student: student.to_owned(),
+            taken_at: Utc.with_ymd_and_hms(2024, 10, day, 12, 0, 0).unwrap(),
+            schedule: Vec::new(),
+            run: 1,
+            classes: vec![ClassAssignments {
+                course: "English 2 Adv".to_owned(),
+                average: Some(average),
+                assignments: vec![Assignment {
+                    name: "Essay".to_owned(),
+                    category: "Major Grades".to_owned(),
+                    date_due: None,
+                    date_assigned: None,
+                    score: Some(average),
+                    total_points: Some(100.0),
+                    status: AssignmentStatus::Normal,
+                }],
+            }],
+            transcript: Transcript {
+                entries: vec![TranscriptEntry::new("English 1 Adv".to_owned(), 97.0)],
+            },
+        }
+    }
+
+    #[test]
+    fn snapshot_round_trip() {
+        let store = SnapshotStore::open_in_memory().unwrap();
+
+        let first = store.save(&snapshot("123456", 1, 90.0)).unwrap();
+        let second = store.save(&snapshot("123456", 8, 95.0)).unwrap();
+        store.save(&snapshot("654321", 9, 80.0)).unwrap();
+
+        assert_eq!(
+            store.get(first).unwrap().unwrap().snapshot,
+            snapshot("123456", 1, 90.0)
+        );
+        assert_eq!(store.latest("123456").unwrap().unwrap().id, second);
+
+        let cutoff = Utc.with_ymd_and_hms(2024, 10, 5, 0, 0, 0).unwrap();
+        assert_eq!(
+            store.latest_before("123456", cutoff).unwrap().unwrap().id,
+            first
+        );
+
+        let history = store.history("123456").unwrap();
+        assert_eq!(
+            history.iter().map(|s| s.id).collect::<Vec<_>>(),
+            [first, second]
+        );
+        assert!(store.latest("nobody").unwrap().is_none());
+    }
+}
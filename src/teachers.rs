@@ -0,0 +1,192 @@
+use std::fmt::Display;
+
+use scraper::ElementRef;
+
+use crate::{html, schedule::Course};
+
+/// A teacher, as listed in the schedule.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Teacher {
+    /// The teacher's name, as HAC shows it (eg. "Smith, Jane").
+    pub name: String,
+    /// The address the teacher's name links to, if any.
+    pub email: Option<String>,
+    /// The district staff id, when HAC includes one in the teacher's link.
+    pub staff_id: Option<String>,
+}
+
+impl Teacher {
+    /// Reads a teacher from a schedule cell, which usually holds a `mailto:` link around the
+    /// teacher's name.
+    pub(crate) fn from_cell(cell: ElementRef) -> Self {
+        let links = cell
+            .descendants()
+            .filter_map(ElementRef::wrap)
+            .flat_map(|e| [e.attr("href"), e.attr("onclick")])
+            .flatten()
+            .collect::<Vec<_>>();
+
+        let email = links
+            .iter()
+            .find_map(|link| link.strip_prefix("mailto:"))
+            .map(|address| address.split('?').next().unwrap_or(address).trim())
+            .filter(|address| !address.is_empty())
+            .map(str::to_owned);
+
+        let staff_id = links.iter().find_map(|link| staff_id(link));
+
+        Self {
+            name: html::text(cell),
+            email,
+            staff_id,
+        }
+    }
+
+    /// Whether two schedule rows name the same teacher: by email if both rows have one, then by
+    /// staff id, then by name, since HAC leaves the link off some rows.
+    fn is_same(&self, other: &Teacher) -> bool {
+        if let (Some(a), Some(b)) = (&self.email, &other.email) {
+            a.eq_ignore_ascii_case(b)
+        } else if let (Some(a), Some(b)) = (&self.staff_id, &other.staff_id) {
+            a == b
+        } else {
+            self.name.to_lowercase() == other.name.to_lowercase()
+        }
+    }
+}
+
+/// Finds a `staffid=1234` style parameter in a link or script.
+fn staff_id(link: &str) -> Option<String> {
+    // ASCII lowercasing keeps byte offsets the same, so they can be used to slice `link`
+    let lower = link.to_ascii_lowercase();
+    let start = lower.find("staffid=")? + "staffid=".len();
+
+    let id = link[start..]
+        .chars()
+        .take_while(char::is_ascii_alphanumeric)
+        .collect::<String>();
+
+    (!id.is_empty()).then_some(id)
+}
+
+impl Display for Teacher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// A teacher and the courses they teach the student.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TeacherEntry {
+    pub teacher: Teacher,
+    /// The names of the courses, in schedule order.
+    pub courses: Vec<String>,
+}
+
+/// Lists each of the student's teachers once, with every course they teach, sorted by name.
+/// Dropped courses are left out.
+pub fn teacher_directory(courses: &[Course]) -> Vec<TeacherEntry> {
+    let mut directory: Vec<TeacherEntry> = Vec::new();
+
+    for course in courses.iter().filter(|c| !c.is_dropped()) {
+        let teacher = course.teacher();
+
+        if teacher.name.is_empty() {
+            continue;
+        }
+
+        match directory
+            .iter_mut()
+            .find(|entry| entry.teacher.is_same(teacher))
+        {
+            Some(entry) => {
+                if !entry.courses.iter().any(|c| c == course.name()) {
+                    entry.courses.push(course.name().to_owned());
+                }
+
+                // a later row may have details the first one was missing
+                if entry.teacher.email.is_none() {
+                    entry.teacher.email.clone_from(&teacher.email);
+                }
+                if entry.teacher.staff_id.is_none() {
+                    entry.teacher.staff_id.clone_from(&teacher.staff_id);
+                }
+            }
+            None => directory.push(TeacherEntry {
+                teacher: teacher.clone(),
+                courses: vec![course.name().to_owned()],
+            }),
+        }
+    }
+
+    directory.sort_by(|a, b| a.teacher.name.cmp(&b.teacher.name));
+
+    directory
+}
+
+#[cfg(test)]
+mod tests {
+    use scraper::Html;
+
+    use super::*;
+
+    #[test]
+    fn directory() {
+        let document = Html::parse_document(
+            r#"
+            <table id="plnMain_dgSchedule">
+                <tr class="sg-asp-table-header-row">
+                    <td>Course</td><td>Description</td><td>Periods</td><td>Teacher</td>
+                    <td>Room</td><td>Days</td>
+                </tr>
+                <tr class="sg-asp-table-data-row">
+                    <td>ENG2200A - 3</td><td>English 2 Adv</td><td>2</td>
+                    <td><a href="mailto:Jane.Smith@friscoisd.org">Smith, Jane</a></td>
+                    <td>B204</td><td>A</td>
+                </tr>
+                <tr class="sg-asp-table-data-row">
+                    <td>ENG2300 - 1</td><td>Creative Writing</td><td>3</td>
+                    <td><a href="mailto:jane.smith@friscoisd.org?subject=Hi"
+                           onclick="showStaff('StaffID=50123')">Smith, Jane</a></td>
+                    <td>B204</td><td>B</td>
+                </tr>
+                <tr class="sg-asp-table-data-row">
+                    <td>CHM1100A - 1</td><td>Chemistry Adv</td><td>4</td><td>Lee, Sam</td>
+                    <td>C110</td><td>A</td>
+                </tr>
+                <tr class="sg-asp-table-data-row">
+                    <td>CHM1100A - 2</td><td>Chemistry Adv Lab</td><td>5</td>
+                    <td><a href="mailto:sam.lee@friscoisd.org">Lee, Sam</a></td>
+                    <td>C110</td><td>A</td>
+                </tr>
+            </table>
+            "#,
+        );
+        let courses = crate::schedule::parse_schedule(&document).unwrap();
+
+        assert_eq!(
+            courses[1].teacher(),
+            &Teacher {
+                name: "Smith, Jane".to_owned(),
+                email: Some("jane.smith@friscoisd.org".to_owned()),
+                staff_id: Some("50123".to_owned()),
+            }
+        );
+
+        let directory = teacher_directory(&courses);
+
+        assert_eq!(directory.len(), 2);
+        assert_eq!(directory[0].teacher.name, "Lee, Sam");
+        assert_eq!(
+            directory[0].teacher.email.as_deref(),
+            Some("sam.lee@friscoisd.org")
+        );
+        assert_eq!(directory[0].courses, ["Chemistry Adv", "Chemistry Adv Lab"]);
+        assert_eq!(directory[1].courses, ["English 2 Adv", "Creative Writing"]);
+        assert_eq!(directory[1].teacher.staff_id.as_deref(), Some("50123"));
+
+        assert_eq!(staff_id("İ staffID=42"), Some("42".to_owned()));
+    }
+}
@@ -1,31 +1,172 @@
-use std::collections::HashMap;
+use std::{convert::Infallible, fmt::Display, str::FromStr};
 
-use anyhow::{anyhow, bail, Result};
+use anyhow::{bail, Result};
 use reqwest::blocking::Client;
-use scraper::{selectable::Selectable, Html, Selector};
+use scraper::{selectable::Selectable, Html};
 
-use crate::selector;
+use crate::{
+    catalog::{Catalog, Classification, CourseId},
+    client, html,
+    identity::CourseResolver,
+    policy::GpaPolicy,
+    selector,
+};
 
 const TRANSCRIPT_PAGE_URL: &str =
     "https://hac.friscoisd.org/HomeAccess/Content/Student/Transcript.aspx";
 
 /// The list of grade entries.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Transcript {
     /// All entries present.
     pub entries: Vec<TranscriptEntry>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TranscriptEntry {
     pub weightage: f32,
-    pub grade: f32,
+    pub grade: Grade,
     pub name: String,
+    /// The school year the grade was earned in (eg. "2023-2024"), if known.
+    pub year: Option<String>,
+    /// The course code (eg. "ENG2200A"), if known.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub code: Option<String>,
+    /// The credits the course earned, if the transcript lists them.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub credits: Option<f32>,
+}
+
+/// A grade as HAC shows it: usually a number, but some courses are graded with a mark instead.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Grade {
+    Numeric(f32),
+    /// `P`
+    Pass,
+    /// `F`
+    Fail,
+    /// `CR`, for credit earned without a grade (eg. credit by exam).
+    Credit,
+    /// `NC`
+    NoCredit,
+    /// `I`
+    Incomplete,
+    /// `W`
+    Withdrawn,
+    /// Any other mark, as HAC shows it.
+    Other(String),
+}
+
+impl Grade {
+    /// The numeric grade, or `None` for marks.
+    pub fn numeric(&self) -> Option<f32> {
+        match self {
+            Grade::Numeric(grade) => Some(*grade),
+            _ => None,
+        }
+    }
+
+    /// Whether the grade earns credit. Marks without a clear meaning don't.
+    pub fn is_passing(&self) -> bool {
+        match self {
+            Grade::Numeric(grade) => grade.round() >= 70.0,
+            Grade::Pass | Grade::Credit => true,
+            _ => false,
+        }
+    }
+}
+
+impl From<f32> for Grade {
+    fn from(grade: f32) -> Self {
+        Grade::Numeric(grade)
+    }
+}
+
+impl FromStr for Grade {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        if let Ok(grade) = s.parse::<f32>() {
+            return Ok(Grade::Numeric(grade));
+        }
+
+        Ok(match s.to_uppercase().as_str() {
+            "P" | "PASS" => Grade::Pass,
+            "F" | "FAIL" => Grade::Fail,
+            "CR" | "CREDIT" => Grade::Credit,
+            "NC" | "NCR" | "NO CREDIT" => Grade::NoCredit,
+            "I" | "INC" => Grade::Incomplete,
+            "W" | "WD" => Grade::Withdrawn,
+            _ => Grade::Other(s.to_owned()),
+        })
+    }
+}
+
+impl Display for Grade {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Grade::Numeric(grade) => write!(f, "{grade}"),
+            Grade::Pass => write!(f, "P"),
+            Grade::Fail => write!(f, "F"),
+            Grade::Credit => write!(f, "CR"),
+            Grade::NoCredit => write!(f, "NC"),
+            Grade::Incomplete => write!(f, "I"),
+            Grade::Withdrawn => write!(f, "W"),
+            Grade::Other(mark) => write!(f, "{mark}"),
+        }
+    }
+}
+
+/// Numeric grades are serialized as numbers and marks as the text HAC shows (eg. `"P"`).
+#[cfg(feature = "serde")]
+impl serde::Serialize for Grade {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Grade::Numeric(grade) => serializer.serialize_f32(*grade),
+            mark => serializer.collect_str(mark),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Grade {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Numeric(f32),
+            Mark(String),
+        }
+
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Numeric(grade) => Grade::Numeric(grade),
+            Repr::Mark(mark) => mark.parse().unwrap_or_else(|e: Infallible| match e {}),
+        })
+    }
+}
+
+/// The scale used to convert a grade into grade points.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum GpaScale {
+    /// The district's weighted scale, where a 100 is worth 5.0, 5.5 or 6.0 points depending on the
+    /// course's weightage, and every point below 100 loses a tenth of a grade point.
+    #[default]
+    Weighted,
+    /// The traditional unweighted 4.0 scale (A = 4, B = 3, C = 2, failing = 0).
+    Unweighted,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum MergeStrategy {
-    /// Transcript entries of the same name are combined into one transcript entry with the
+    /// Transcript entries of the same course are combined into one transcript entry with the
     /// mean of the grades.
     Average,
     /// Transcript entries of the same name are left as seperate entries.
@@ -34,11 +175,37 @@ pub enum MergeStrategy {
 
 impl Transcript {
     pub fn gpa(&self) -> f32 {
-        let sum: f32 = self.entries.iter().map(|e| e.gpa()).sum();
+        self.gpa_on(GpaScale::Weighted)
+    }
 
-        sum / self.entries.len() as f32
+    /// The GPA under the district's policy, which leaves out courses graded with marks (eg. "P")
+    /// and the likes of local credit.
+    pub fn gpa_on(&self, scale: GpaScale) -> f32 {
+        self.gpa_with(GpaPolicy::bundled(), scale)
     }
 
+    /// The mean grade points of the entries `policy` counts.
+    pub fn gpa_with(&self, policy: &GpaPolicy, scale: GpaScale) -> f32 {
+        let points = self
+            .entries
+            .iter()
+            .filter(|e| policy.exclusion(e).is_none())
+            .filter_map(|e| e.gpa_on(scale))
+            .collect::<Vec<_>>();
+
+        points.iter().sum::<f32>() / points.len() as f32
+    }
+
+    /// Recomputes every entry's weightage with `catalog`, eg. one with local overrides.
+    pub fn reweigh(&mut self, catalog: &Catalog) {
+        for entry in &mut self.entries {
+            entry.weightage = entry.classify(catalog).level.weightage();
+        }
+    }
+
+    /// Combines transcripts from several sources. With [`MergeStrategy::Average`], entries are
+    /// matched by course code, or failing that by name (see [`crate::CourseIdentity`]), so a
+    /// course named differently on two pages is still counted once.
     pub fn combine(transcripts: &[Transcript], strategy: MergeStrategy) -> Transcript {
         let entries = transcripts
             .iter()
@@ -53,25 +220,40 @@ impl Transcript {
     }
 
     fn merge_average<'a>(entries: impl Iterator<Item = &'a TranscriptEntry>) -> Self {
-        // if we find duplicates, keep their average
-        let mut seen: HashMap<String, Vec<TranscriptEntry>> = HashMap::new();
+        // if we find the same course more than once, keep its average
+        let mut resolver = CourseResolver::new();
+        let mut seen: Vec<Vec<&TranscriptEntry>> = Vec::new();
 
         for entry in entries {
-            seen.entry(entry.name.clone())
-                .or_default()
-                .push(entry.clone());
+            let index = resolver.resolve(entry.code.as_deref(), &entry.name);
+
+            match seen.get_mut(index) {
+                Some(same) => same.push(entry),
+                None => seen.push(vec![entry]),
+            }
         }
 
         let entries = seen
             .into_iter()
-            .map(|(k, v)| {
-                let average = v.iter().map(|e| e.grade).sum::<f32>() / v.len() as f32;
-                let average = average.round();
+            .map(|v| {
+                let grades = v
+                    .iter()
+                    .filter_map(|e| e.grade.numeric())
+                    .collect::<Vec<_>>();
+
+                // marks can't be averaged, so a course without any numeric grade keeps its first
+                let grade = match grades.len() {
+                    0 => v[0].grade.clone(),
+                    n => Grade::Numeric((grades.iter().sum::<f32>() / n as f32).round()),
+                };
 
                 TranscriptEntry {
-                    name: k,
+                    name: v[0].name.clone(),
                     weightage: v[0].weightage,
-                    grade: average,
+                    grade,
+                    year: v[0].year.clone(),
+                    code: v.iter().find_map(|e| e.code.clone()),
+                    credits: v.iter().find_map(|e| e.credits),
                 }
             })
             .collect::<Vec<_>>();
@@ -81,71 +263,177 @@ impl Transcript {
 }
 
 impl TranscriptEntry {
-    pub fn new(name: String, grade: f32) -> Self {
+    pub fn new(name: String, grade: impl Into<Grade>) -> Self {
         Self {
             weightage: Self::get_weightage(&name),
             name,
-            grade,
+            grade: grade.into(),
+            year: None,
+            code: None,
+            credits: None,
         }
     }
 
-    pub fn get_weightage(name: &str) -> f32 {
-        if name.contains("AP") {
-            6.0
-        } else if name.contains("Adv") {
-            5.5
-        } else {
-            5.0
+    /// Like [`TranscriptEntry::new`], but weighted by the course's id (eg. "ENG2200A - 3") as
+    /// well as its name.
+    pub fn with_id(id: &str, name: String, grade: impl Into<Grade>) -> Self {
+        let id = CourseId::parse(id);
+
+        Self {
+            weightage: Catalog::bundled()
+                .classify(id.as_ref(), &name)
+                .level
+                .weightage(),
+            code: id.map(|id| id.code),
+            ..Self::new(name, grade)
         }
     }
 
-    pub fn gpa(&self) -> f32 {
-        self.weightage - (100.0 - self.grade.round()) / 10.0
+    /// The weightage of a course with this name in the bundled catalog.
+    pub fn get_weightage(name: &str) -> f32 {
+        Catalog::bundled().classify(None, name).level.weightage()
     }
+
+    /// The course's subject area and level, according to `catalog`.
+    pub fn classify(&self, catalog: &Catalog) -> Classification {
+        let id = self.code.as_deref().and_then(CourseId::parse);
+
+        catalog.classify(id.as_ref(), &self.name)
+    }
+
+    /// The grade points the entry earns, or `None` if it isn't graded with a number.
+    pub fn gpa(&self) -> Option<f32> {
+        self.gpa_on(GpaScale::Weighted)
+    }
+
+    pub fn gpa_on(&self, scale: GpaScale) -> Option<f32> {
+        let grade = self.grade.numeric()?.round();
+
+        Some(match scale {
+            GpaScale::Weighted => self.weightage - (100.0 - grade) / 10.0,
+            GpaScale::Unweighted => match grade {
+                90.0.. => 4.0,
+                80.0.. => 3.0,
+                70.0.. => 2.0,
+                _ => 0.0,
+            },
+        })
+    }
+}
+
+/// The cumulative GPA and class rank at the bottom of the transcript page, as the district
+/// calculated them when the transcript was last posted.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TranscriptSummary {
+    /// The weighted cumulative GPA.
+    pub gpa: Option<f32>,
+    pub rank: Option<u32>,
+    /// The number of students ranked.
+    pub class_size: Option<u32>,
 }
 
 pub fn get_transcript(client: &Client) -> Result<Transcript> {
-    let transcript_page_resp = client.get(TRANSCRIPT_PAGE_URL).send()?.text()?;
-    let document = Html::parse_document(&transcript_page_resp);
+    let transcript_page_resp = client::fetch_page(client.get(TRANSCRIPT_PAGE_URL))?;
 
+    Ok(parse_transcript(&Html::parse_document(
+        &transcript_page_resp,
+    )))
+}
+
+pub fn get_transcript_summary(client: &Client) -> Result<TranscriptSummary> {
+    let transcript_page_resp = client::fetch_page(client.get(TRANSCRIPT_PAGE_URL))?;
+
+    Ok(parse_transcript_summary(&Html::parse_document(
+        &transcript_page_resp,
+    )))
+}
+
+/// Reads the cumulative GPA and rank (eg. "12 / 834" or "12 of 834"). Either is `None` when HAC
+/// doesn't show it, which some districts do until junior year.
+pub(crate) fn parse_transcript_summary(document: &Html) -> TranscriptSummary {
+    let gpa = document
+        .select(selector!(r#"span[id*="lblGPACum"]"#))
+        .find_map(|span| html::text(span).parse::<f32>().ok());
+
+    let rank = document
+        .select(selector!(r#"span[id*="lblGPARank"]"#))
+        .find_map(|span| {
+            let text = html::text(span).to_lowercase();
+            let (rank, size) = text.split_once('/').or_else(|| text.split_once(" of "))?;
+
+            Some((rank.trim().parse().ok()?, size.trim().parse().ok()?))
+        });
+
+    TranscriptSummary {
+        gpa,
+        rank: rank.map(|(rank, _)| rank),
+        class_size: rank.map(|(_, size)| size),
+    }
+}
+
+/// Reads every graded course off the transcript page, year by year. Columns are found by their
+/// headers where HAC includes them, and otherwise by position.
+pub(crate) fn parse_transcript(document: &Html) -> Transcript {
     let mut cumulative_entries = Vec::new();
 
     for group in document.select(selector!(".sg-transcript-group")) {
+        let year = group
+            .select(selector!(r#"span[id*="lblYearValue"]"#))
+            .next()
+            .map(html::text);
+
+        let headers = group
+            .select(selector!(".sg-asp-table-header-row"))
+            .next()
+            .map(html::header_names)
+            .unwrap_or_default();
+
+        let column = |names: &[&str], default: Option<usize>| {
+            names
+                .iter()
+                .find_map(|name| html::column(&headers, name))
+                .or(default)
+        };
+
+        let code_column = column(&["Course"], Some(0));
+        let name_column = column(&["Description"], Some(1));
+        let grade_column = column(&["Grade", "Sem1", "Final"], Some(2));
+        let credits_column = column(&["CR", "Credit", "Credits"], None);
+
         let year_entries = group
-            .select(&Selector::parse(".sg-asp-table-data-row").unwrap())
+            .select(selector!(".sg-asp-table-data-row"))
             .map(|entry| {
-                let mut children = entry
-                    .child_elements()
-                    .filter_map(|cell| cell.text().next())
-                    .skip(1);
-
-                let name = children
-                    .next()
-                    .ok_or(anyhow!("missing course name"))?
-                    .to_owned();
-
-                let grade = children
-                    .next()
-                    .ok_or(anyhow!("missing course grade"))?
-                    .parse::<f32>()?;
-
-                if name.split(" ").any(|word| word == "EA") {
-                    bail!("skip")
+                let cells = entry.child_elements().map(html::text).collect::<Vec<_>>();
+                let cell = |column: Option<usize>| column.and_then(|i| cells.get(i));
+
+                let (Some(code), Some(name), Some(grade)) =
+                    (cell(code_column), cell(name_column), cell(grade_column))
+                else {
+                    bail!("missing transcript columns");
+                };
+
+                if grade.is_empty() {
+                    bail!("no grade yet");
                 }
 
-                Ok(TranscriptEntry::new(name, grade))
+                let grade = grade.parse::<Grade>().unwrap_or_else(|e| match e {});
+
+                Ok(TranscriptEntry {
+                    year: year.clone(),
+                    credits: cell(credits_column).and_then(|c| c.parse().ok()),
+                    ..TranscriptEntry::with_id(code, name.clone(), grade)
+                })
             })
             .filter_map(Result::ok)
             .collect::<Vec<_>>();
 
-        dbg!(&year_entries);
-
         cumulative_entries.extend(year_entries);
     }
 
-    Ok(Transcript {
+    Transcript {
         entries: cumulative_entries,
-    })
+    }
 }
 
 #[cfg(test)]
@@ -159,21 +447,30 @@ mod tests {
                 entries: vec![
                     TranscriptEntry {
                         name: "Class A".to_owned(),
-                        grade: 90.0,
+                        grade: Grade::Numeric(90.0),
                         weightage: 5.5,
+                        year: None,
+                        code: None,
+                        credits: None,
                     },
                     TranscriptEntry {
                         name: "Class B".to_owned(),
-                        grade: 100.0,
+                        grade: Grade::Numeric(100.0),
                         weightage: 5.0,
+                        year: None,
+                        code: None,
+                        credits: None,
                     },
                 ],
             },
             Transcript {
                 entries: vec![TranscriptEntry {
                     name: "Class A".to_owned(),
-                    grade: 100.0,
+                    grade: Grade::Numeric(100.0),
                     weightage: 5.5,
+                    year: None,
+                    code: None,
+                    credits: None,
                 }],
             },
         ];
@@ -186,13 +483,19 @@ mod tests {
             vec![
                 TranscriptEntry {
                     name: "Class A".to_owned(),
-                    grade: 95.0,
+                    grade: Grade::Numeric(95.0),
                     weightage: 5.5,
+                    year: None,
+                    code: None,
+                    credits: None,
                 },
                 TranscriptEntry {
                     name: "Class B".to_owned(),
-                    grade: 100.0,
+                    grade: Grade::Numeric(100.0),
                     weightage: 5.0,
+                    year: None,
+                    code: None,
+                    credits: None,
                 },
             ]
         );
@@ -205,20 +508,167 @@ mod tests {
             vec![
                 TranscriptEntry {
                     name: "Class A".to_owned(),
-                    grade: 90.0,
+                    grade: Grade::Numeric(90.0),
                     weightage: 5.5,
+                    year: None,
+                    code: None,
+                    credits: None,
                 },
                 TranscriptEntry {
                     name: "Class A".to_owned(),
-                    grade: 100.0,
+                    grade: Grade::Numeric(100.0),
                     weightage: 5.5,
+                    year: None,
+                    code: None,
+                    credits: None,
                 },
                 TranscriptEntry {
                     name: "Class B".to_owned(),
-                    grade: 100.0,
+                    grade: Grade::Numeric(100.0),
                     weightage: 5.0,
+                    year: None,
+                    code: None,
+                    credits: None,
                 },
             ]
         );
     }
+
+    #[test]
+    fn combines_renamed_courses() {
+        let transcripts = [
+            Transcript {
+                entries: vec![
+                    TranscriptEntry::with_id(
+                        "CSC4100 - 1",
+                        "AP Computer Science A".to_owned(),
+                        94.0,
+                    ),
+                    TranscriptEntry::new("English 2 Adv".to_owned(), 90.0),
+                ],
+            },
+            Transcript {
+                entries: vec![
+                    TranscriptEntry::new("AP Comp Sci A S1".to_owned(), 98.0),
+                    TranscriptEntry::with_id("ENG2200A - 3", "Eng II Adv".to_owned(), 100.0),
+                    TranscriptEntry::new("English 3 Adv".to_owned(), 80.0),
+                ],
+            },
+        ];
+
+        let entries = Transcript::combine(&transcripts, MergeStrategy::Average).entries;
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].name, "AP Computer Science A");
+        assert_eq!(entries[0].grade, Grade::Numeric(96.0));
+        assert_eq!(entries[0].code.as_deref(), Some("CSC4100"));
+        assert_eq!(entries[1].grade, Grade::Numeric(95.0));
+        assert_eq!(entries[1].code.as_deref(), Some("ENG2200A"));
+        assert_eq!(entries[2].name, "English 3 Adv");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_schema() {
+        // the CLI's JSON output embeds these models; changing their shape needs a schema bump
+        let transcript = Transcript {
+            entries: vec![TranscriptEntry::new("Calculus BC AP".to_owned(), 98.0)],
+        };
+
+        assert_eq!(
+            serde_json::to_value(&transcript).unwrap(),
+            serde_json::json!({
+                "entries": [{
+                    "weightage": 6.0,
+                    "grade": 98.0,
+                    "name": "Calculus BC AP",
+                    "year": null,
+                    "code": null,
+                    "credits": null
+                }]
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(Grade::Pass).unwrap(),
+            serde_json::json!("P")
+        );
+        assert_eq!(
+            serde_json::from_value::<Grade>(serde_json::json!("CR")).unwrap(),
+            Grade::Credit
+        );
+        assert_eq!(
+            serde_json::to_value(GpaScale::Unweighted).unwrap(),
+            serde_json::json!("unweighted")
+        );
+    }
+
+    #[test]
+    fn transcript_page() {
+        let document = Html::parse_document(
+            r#"
+            <div class="sg-transcript-group">
+                <span id="plnMain_rpTranscriptGroup_lblYearValue_0">2023-2024</span>
+                <table>
+                    <tr class="sg-asp-table-header-row">
+                        <td>Course</td><td>Description</td><td>Sem1</td><td>CR</td>
+                    </tr>
+                    <tr class="sg-asp-table-data-row">
+                        <td>ENG1200A</td><td>English 1 Adv</td><td>97</td><td>0.5</td>
+                    </tr>
+                    <tr class="sg-asp-table-data-row">
+                        <td>LDR1100</td><td>Teen Leadership</td><td>P</td><td>0.5</td>
+                    </tr>
+                    <tr class="sg-asp-table-data-row">
+                        <td>MTH1300</td><td>Geometry</td><td></td><td></td>
+                    </tr>
+                </table>
+            </div>
+            "#,
+        );
+
+        let entries = parse_transcript(&document).entries;
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].code.as_deref(), Some("ENG1200A"));
+        assert_eq!(entries[0].weightage, 5.5);
+        assert_eq!(entries[0].credits, Some(0.5));
+        assert_eq!(entries[0].year.as_deref(), Some("2023-2024"));
+        assert_eq!(entries[1].grade, Grade::Pass);
+    }
+
+    #[test]
+    fn transcript_summary() {
+        let document = Html::parse_document(
+            r#"
+            <table id="plnMain_rpTranscriptGroup_tblCumGPAInfo">
+                <tr>
+                    <td><span id="plnMain_rpTranscriptGroup_lblGPACum1">5.2143</span></td>
+                    <td><span id="plnMain_rpTranscriptGroup_lblGPARank1">57 / 812</span></td>
+                </tr>
+            </table>
+            "#,
+        );
+
+        assert_eq!(
+            parse_transcript_summary(&document),
+            TranscriptSummary {
+                gpa: Some(5.2143),
+                rank: Some(57),
+                class_size: Some(812),
+            }
+        );
+        assert_eq!(
+            parse_transcript_summary(&Html::parse_document("")),
+            TranscriptSummary::default()
+        );
+    }
+
+    #[test]
+    fn marks() {
+        assert_eq!("98".parse(), Ok(Grade::Numeric(98.0)));
+        assert_eq!("p".parse(), Ok(Grade::Pass));
+        assert_eq!(" CR ".parse(), Ok(Grade::Credit));
+        assert_eq!("EX".parse(), Ok(Grade::Other("EX".to_owned())));
+        assert_eq!(Grade::NoCredit.to_string(), "NC");
+    }
 }
@@ -0,0 +1,262 @@
+//! Grade trends over a student's snapshot history.
+
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+
+use crate::{assignments::Assignment, changes, snapshot::Snapshot};
+
+/// How long a report card run usually lasts, used when its end date isn't known.
+const RUN_LENGTH: TimeDelta = TimeDelta::weeks(9);
+
+/// How many of the most recent points [`ClassTrend::momentum`] looks at.
+const MOMENTUM_POINTS: usize = 3;
+
+const SECONDS_PER_DAY: f64 = 24.0 * 60.0 * 60.0;
+
+/// A class's average at the time a snapshot was taken.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AveragePoint {
+    pub taken_at: DateTime<Utc>,
+    pub average: f32,
+}
+
+/// An assignment, and how much the class average moved in the snapshot it was posted or rescored
+/// in.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AssignmentImpact {
+    pub assignment: Assignment,
+    pub change: f32,
+}
+
+/// How one class's average has moved over a report card run.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClassTrend {
+    pub course: String,
+    /// The class average in every snapshot of the run that had one, oldest first.
+    pub points: Vec<AveragePoint>,
+    /// The least squares slope of the average, in points per day.
+    pub slope: Option<f32>,
+    /// The slope over the last few snapshots minus the overall slope: positive when the class is
+    /// improving faster (or declining slower) than it has been all run.
+    pub momentum: Option<f32>,
+    /// The assignment behind the largest single move in the average.
+    pub largest_change: Option<AssignmentImpact>,
+    /// The average at the end of the run if the current slope continues, clamped to 0-100 (or
+    /// the current average, if that is already above 100).
+    pub projected: Option<f32>,
+}
+
+impl ClassTrend {
+    pub fn latest(&self) -> Option<f32> {
+        self.points.last().map(|p| p.average)
+    }
+}
+
+/// Guesses when the snapshot's report card run ends: [`RUN_LENGTH`] after the earliest assignment
+/// was assigned.
+pub fn estimate_run_end(snapshot: &Snapshot) -> Option<NaiveDate> {
+    snapshot
+        .classes
+        .iter()
+        .flat_map(|c| &c.assignments)
+        .filter_map(|a| a.date_assigned.or(a.date_due))
+        .min()
+        .map(|start| start + RUN_LENGTH)
+}
+
+/// Computes the trend of every class in the latest snapshot's report card run. `history` must be
+/// one student's snapshots, oldest first (as returned by the snapshot store).
+pub fn trends(history: &[Snapshot], run_end: NaiveDate) -> Vec<ClassTrend> {
+    let Some(latest) = history.last() else {
+        return Vec::new();
+    };
+
+    let run = history
+        .iter()
+        .filter(|s| s.run == latest.run)
+        .collect::<Vec<_>>();
+
+    latest
+        .classes
+        .iter()
+        .map(|class| class_trend(&run, &class.course, run_end))
+        .collect()
+}
+
+fn class_trend(run: &[&Snapshot], course: &str, run_end: NaiveDate) -> ClassTrend {
+    let points = run
+        .iter()
+        .filter_map(|snapshot| {
+            let class = snapshot.classes.iter().find(|c| c.course == course)?;
+
+            Some(AveragePoint {
+                taken_at: snapshot.taken_at,
+                average: class.average?,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let overall = slope(&points);
+
+    let momentum = match overall {
+        Some(overall) if points.len() > MOMENTUM_POINTS => {
+            slope(&points[points.len() - MOMENTUM_POINTS..]).map(|recent| recent - overall)
+        }
+        _ => None,
+    };
+
+    let projected = match (overall, points.last()) {
+        (Some(slope), Some(last)) => {
+            let end = run_end.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+            let days = ((end - last.taken_at).num_seconds() as f64 / SECONDS_PER_DAY).max(0.0);
+            let projected = last.average + slope * days as f32;
+
+            Some(projected.clamp(0.0, last.average.max(100.0)))
+        }
+        _ => None,
+    };
+
+    ClassTrend {
+        course: course.to_owned(),
+        slope: overall,
+        momentum,
+        largest_change: largest_change(run, course),
+        projected,
+        points,
+    }
+}
+
+/// The least squares slope through the points, in points per day. `None` with fewer than two
+/// points or if they were all taken at the same time.
+fn slope(points: &[AveragePoint]) -> Option<f32> {
+    let first = points.first()?.taken_at;
+
+    let xy = points
+        .iter()
+        .map(|p| {
+            let days = (p.taken_at - first).num_seconds() as f64 / SECONDS_PER_DAY;
+            (days, p.average as f64)
+        })
+        .collect::<Vec<_>>();
+
+    let n = xy.len() as f64;
+    let mean_x = xy.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = xy.iter().map(|(_, y)| y).sum::<f64>() / n;
+
+    let covariance = xy
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum::<f64>();
+    let variance = xy.iter().map(|(x, _)| (x - mean_x).powi(2)).sum::<f64>();
+
+    (variance > 0.0).then(|| (covariance / variance) as f32)
+}
+
+/// Finds the biggest move in the class's average between consecutive snapshots and blames it on
+/// the assignment posted or rescored in between that is furthest from the old average.
+fn largest_change(run: &[&Snapshot], course: &str) -> Option<AssignmentImpact> {
+    run.windows(2)
+        .filter_map(|pair| {
+            let changes = changes::diff(pair[0], pair[1]);
+
+            let average = changes
+                .averages_changed
+                .iter()
+                .find(|c| c.course == course)?;
+            let (old, new) = (average.old?, average.new?);
+
+            let posted = changes
+                .assignments_posted
+                .iter()
+                .filter(|p| p.course == course)
+                .map(|p| &p.assignment);
+            let rescored = changes
+                .scores_changed
+                .iter()
+                .filter(|c| c.course == course)
+                .map(|c| &c.assignment);
+
+            let assignment = posted
+                .chain(rescored)
+                .filter_map(|a| Some((a, (a.percentage()? - old).abs())))
+                .max_by(|(_, a), (_, b)| a.total_cmp(b))?
+                .0;
+
+            Some(AssignmentImpact {
+                assignment: assignment.clone(),
+                change: new - old,
+            })
+        })
+        .max_by(|a, b| a.change.abs().total_cmp(&b.change.abs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        assignments::{AssignmentStatus, ClassAssignments},
+        transcript::Transcript,
+    };
+
+    use super::*;
+
+    fn snapshot(day: u32, average: f32, scores: &[f32]) -> Snapshot {
+        let date = NaiveDate::from_ymd_opt(2024, 9, day).unwrap();
+
+        let assignments = scores
+            .iter()
+            .enumerate()
+            .map(|(i, score)| Assignment {
+                name: format!("Quiz {}", i + 1),
+                category: "Minor".to_owned(),
+                date_due: NaiveDate::from_ymd_opt(2024, 9, 2 + i as u32),
+                date_assigned: NaiveDate::from_ymd_opt(2024, 9, 1),
+                score: Some(*score),
+                total_points: Some(100.0),
+                status: AssignmentStatus::Normal,
+            })
+            .collect();
+
+        Snapshot {
+            student: "123456".to_owned(),
+            taken_at: date.and_hms_opt(12, 0, 0).unwrap().and_utc(),
+            schedule: Vec::new(),
+            run: 1,
+            classes: vec![ClassAssignments {
+                course: "Chemistry".to_owned(),
+                average: Some(average),
+                assignments,
+            }],
+            transcript: Transcript {
+                entries: Vec::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn class_trend() {
+        let history = vec![
+            snapshot(2, 80.0, &[80.0]),
+            snapshot(3, 85.0, &[80.0, 90.0]),
+            snapshot(4, 70.0, &[80.0, 90.0, 40.0]),
+            snapshot(5, 75.0, &[80.0, 90.0, 40.0, 90.0]),
+        ];
+
+        let run_end = estimate_run_end(history.last().unwrap()).unwrap();
+        assert_eq!(run_end, NaiveDate::from_ymd_opt(2024, 11, 3).unwrap());
+
+        let trends = trends(&history, run_end);
+        let trend = &trends[0];
+
+        assert_eq!(trend.points.len(), 4);
+        assert_eq!(trend.latest(), Some(75.0));
+        assert!(trend.slope.unwrap() < 0.0);
+        assert!(trend.momentum.unwrap() < 0.0);
+        assert_eq!(trend.projected, Some(0.0));
+
+        let largest = trend.largest_change.as_ref().unwrap();
+        assert_eq!(largest.assignment.name, "Quiz 3");
+        assert_eq!(largest.change, -15.0);
+    }
+}